@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use crate::{
+    exceptions::{alias_error::AliasError, commands::ShellError},
+    port::command::{Command, CommandResult},
+    shell::alias::AliasTable,
+};
+
+pub struct Alias {
+    aliases: Arc<AliasTable>,
+}
+
+impl Alias {
+    pub fn new(aliases: Arc<AliasTable>) -> Self {
+        Self { aliases }
+    }
+}
+
+impl Command for Alias {
+    fn execute(&self, args: &[String]) -> Result<CommandResult, ShellError> {
+        if args.is_empty() {
+            let listing: String = self
+                .aliases
+                .list()
+                .iter()
+                .map(|(name, value)| AliasTable::definition(name, value))
+                .collect();
+            return Ok(CommandResult::stdout(listing));
+        }
+
+        let mut stdout = String::new();
+        let mut stderr = String::new();
+
+        for arg in args {
+            match arg.split_once('=') {
+                Some((name, value)) if AliasTable::is_valid_name(name) => {
+                    self.aliases.set(name, value)
+                }
+                Some((name, _)) => {
+                    stderr.push_str(&format!("{}\n", AliasError::InvalidName(name.to_owned())))
+                }
+                None => match self.aliases.get(arg) {
+                    Some(value) => stdout.push_str(&AliasTable::definition(arg, &value)),
+                    None => stderr.push_str(&format!("{}\n", AliasError::NotFound(arg.clone()))),
+                },
+            }
+        }
+
+        if stderr.is_empty() {
+            return Ok(CommandResult::stdout(stdout));
+        }
+        Ok(CommandResult::Failure(stdout, stderr, 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (Arc<AliasTable>, Alias) {
+        let aliases = Arc::new(AliasTable::default());
+        (Arc::clone(&aliases), Alias::new(aliases))
+    }
+
+    #[test]
+    fn define_and_print_alias() {
+        let (aliases, alias) = setup();
+        let result = alias.execute(&["ll=ls -la".to_string()]);
+        assert_eq!(result, Ok(CommandResult::stdout("")));
+        assert_eq!(aliases.get("ll"), Some("ls -la".to_string()));
+
+        let result = alias.execute(&["ll".to_string()]);
+        assert_eq!(result, Ok(CommandResult::stdout("alias ll='ls -la'\n")));
+    }
+
+    #[test]
+    fn list_all_aliases_sorted() {
+        let (aliases, alias) = setup();
+        aliases.set("gs", "git status");
+        aliases.set("q", "echo it's");
+
+        let result = alias.execute(&[]);
+        assert_eq!(
+            result,
+            Ok(CommandResult::stdout(
+                "alias gs='git status'\nalias q='echo it'\\''s'\n"
+            ))
+        );
+    }
+
+    #[test]
+    fn unknown_alias_fails() {
+        let (_, alias) = setup();
+        let result = alias.execute(&["nope".to_string()]);
+        assert_eq!(
+            result,
+            Ok(CommandResult::Failure(
+                String::new(),
+                "alias: nope: not found\n".to_string(),
+                1
+            ))
+        );
+    }
+
+    #[test]
+    fn invalid_name_fails() {
+        let (aliases, alias) = setup();
+        let result = alias.execute(&["a/b=x".to_string()]);
+        assert_eq!(result.unwrap().exit_status(), 1);
+        assert!(aliases.names().is_empty());
+    }
+}
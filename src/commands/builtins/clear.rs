@@ -0,0 +1,13 @@
+use crate::{
+    exceptions::commands::ShellError,
+    port::command::{Command, CommandResult},
+    shell::input::render::clear_screen,
+};
+
+pub struct Clear;
+
+impl Command for Clear {
+    fn execute(&self, _args: &[String]) -> Result<CommandResult, ShellError> {
+        Ok(CommandResult::stdout(clear_screen()))
+    }
+}
@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use crate::{
+    exceptions::commands::ShellError,
+    port::command::{Command, CommandResult},
+    shell::{
+        completion::{context::CompletionContext, spec::CompletionSpec},
+        path::PathDirsProvider,
+    },
+};
+
+pub struct Compgen {
+    path_dirs: Arc<PathDirsProvider>,
+}
+
+impl Compgen {
+    pub fn new(path_dirs: Arc<PathDirsProvider>) -> Self {
+        Self { path_dirs }
+    }
+}
+
+impl Command for Compgen {
+    fn execute(&self, args: &[String]) -> Result<CommandResult, ShellError> {
+        let parsed = CompletionSpec::parse("compgen", args)?;
+        if parsed.print || parsed.remove {
+            let option = if parsed.print { "-p" } else { "-r" };
+            return Err(ShellError::InvalidOption(format!("compgen: {option}")));
+        }
+
+        let word = parsed.operands.first().map_or("", String::as_str);
+        let context = CompletionContext::argument("compgen", word);
+        let candidates = parsed.spec.candidates(&context, &self.path_dirs);
+        if candidates.is_empty() {
+            return Ok(CommandResult::Failure(String::new(), String::new(), 1));
+        }
+
+        let listing: String = candidates
+            .iter()
+            .map(|candidate| format!("{candidate}\n"))
+            .collect();
+        Ok(CommandResult::stdout(listing))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn setup() -> Compgen {
+        Compgen::new(Arc::new(PathDirsProvider::new(Vec::new())))
+    }
+
+    #[test]
+    fn wordlist_matching_word() {
+        assert_eq!(
+            setup().execute(&args(&["-W", "start stop status", "sta"])),
+            Ok(CommandResult::stdout("start\nstatus\n"))
+        );
+    }
+
+    #[test]
+    fn builtin_commands() {
+        assert_eq!(
+            setup().execute(&args(&["-c", "his"])),
+            Ok(CommandResult::stdout("history\n"))
+        );
+    }
+
+    #[test]
+    fn no_match_fails_silently() {
+        assert_eq!(
+            setup().execute(&args(&["-W", "start", "x"])),
+            Ok(CommandResult::Failure(String::new(), String::new(), 1))
+        );
+        assert_eq!(
+            setup().execute(&args(&["-p"])),
+            Err(ShellError::InvalidOption("compgen: -p".to_string()))
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use crate::{
+    exceptions::{commands::ShellError, completion_error::CompletionError},
+    port::command::{Command, CommandResult},
+    shell::completion::spec::{CompletionSpec, CompletionSpecs},
+};
+
+pub struct Complete {
+    specs: Arc<CompletionSpecs>,
+}
+
+impl Complete {
+    pub fn new(specs: Arc<CompletionSpecs>) -> Self {
+        Self { specs }
+    }
+
+    fn list(&self) -> CommandResult {
+        let listing: String = self
+            .specs
+            .list()
+            .iter()
+            .map(|(name, spec)| format!("{}\n", spec.to_command(name)))
+            .collect();
+        CommandResult::stdout(listing)
+    }
+}
+
+impl Command for Complete {
+    fn execute(&self, args: &[String]) -> Result<CommandResult, ShellError> {
+        let parsed = CompletionSpec::parse("complete", args)?;
+
+        if parsed.operands.is_empty() {
+            if parsed.remove {
+                self.specs.clear();
+                return Ok(CommandResult::Empty);
+            }
+            return Ok(self.list());
+        }
+
+        let mut stdout = String::new();
+        let mut stderr = String::new();
+
+        for name in &parsed.operands {
+            if parsed.print || parsed.remove {
+                let spec = if parsed.remove {
+                    self.specs.remove(name)
+                } else {
+                    self.specs.get(name)
+                };
+                match spec {
+                    Some(spec) if parsed.print => {
+                        stdout.push_str(&format!("{}\n", spec.to_command(name)))
+                    }
+                    Some(_) => {}
+                    None => stderr.push_str(&format!(
+                        "{}\n",
+                        CompletionError::NoSpecification(name.clone())
+                    )),
+                }
+            } else {
+                self.specs.set(name, parsed.spec.clone());
+            }
+        }
+
+        if stderr.is_empty() {
+            return Ok(CommandResult::stdout(stdout));
+        }
+        Ok(CommandResult::Failure(stdout, stderr, 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn setup() -> (Arc<CompletionSpecs>, Complete) {
+        let specs = Arc::new(CompletionSpecs::default());
+        (Arc::clone(&specs), Complete::new(specs))
+    }
+
+    #[test]
+    fn define_and_print_spec() {
+        let (specs, complete) = setup();
+        let result = complete.execute(&args(&["-W", "start stop", "svc", "ctl"]));
+        assert_eq!(result, Ok(CommandResult::stdout("")));
+        assert!(specs.get("ctl").is_some());
+
+        let result = complete.execute(&args(&["-p", "svc"]));
+        assert_eq!(
+            result,
+            Ok(CommandResult::stdout("complete -W 'start stop' svc\n"))
+        );
+        let result = complete.execute(&[]);
+        assert_eq!(
+            result,
+            Ok(CommandResult::stdout(
+                "complete -W 'start stop' ctl\ncomplete -W 'start stop' svc\n"
+            ))
+        );
+    }
+
+    #[test]
+    fn remove_spec() {
+        let (specs, complete) = setup();
+        complete.execute(&args(&["-d", "svc", "ctl"])).unwrap();
+
+        assert_eq!(
+            complete.execute(&args(&["-r", "svc"])),
+            Ok(CommandResult::stdout(""))
+        );
+        assert_eq!(specs.get("svc"), None);
+        assert_eq!(complete.execute(&args(&["-r"])), Ok(CommandResult::Empty));
+        assert!(specs.list().is_empty());
+    }
+
+    #[test]
+    fn missing_spec_fails() {
+        let (_, complete) = setup();
+        assert_eq!(
+            complete.execute(&args(&["-p", "nope"])),
+            Ok(CommandResult::Failure(
+                String::new(),
+                "complete: nope: no completion specification\n".to_string(),
+                1
+            ))
+        );
+    }
+}
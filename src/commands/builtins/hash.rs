@@ -0,0 +1,209 @@
+use std::{path::PathBuf, str::FromStr, sync::Arc};
+
+use crate::{
+    commands::CommandToken,
+    exceptions::{commands::ShellError, hash_error::HashError},
+    port::command::{Command, CommandResult},
+    shell::path::PathDirsProvider,
+};
+
+pub struct Hash {
+    path_dirs: Arc<PathDirsProvider>,
+}
+
+impl Hash {
+    pub fn new(path_dirs: Arc<PathDirsProvider>) -> Self {
+        Self { path_dirs }
+    }
+
+    fn list(&self) -> CommandResult {
+        let commands = self.path_dirs.hashed_commands();
+        if commands.is_empty() {
+            return CommandResult::stdout("hash: hash table empty\n");
+        }
+
+        let listing: String = commands
+            .iter()
+            .map(|(_, command)| format!("{:4}\t{}\n", command.hits, command.path.display()))
+            .collect();
+        CommandResult::stdout(format!("hits\tcommand\n{listing}"))
+    }
+}
+
+impl Command for Hash {
+    fn execute(&self, args: &[String]) -> Result<CommandResult, ShellError> {
+        let mut clear = false;
+        let mut print = false;
+        let mut path = None;
+        let mut names = Vec::new();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--" => break,
+                "-r" => clear = true,
+                "-t" => print = true,
+                "-p" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| ShellError::MissingOptionArgument("hash: -p".to_string()))?;
+                    path = Some(PathBuf::from(value));
+                }
+                option if option.starts_with('-') && option.len() > 1 => {
+                    return Err(ShellError::InvalidOption(format!("hash: {option}")))
+                }
+                _ => {
+                    names.push(arg);
+                    break;
+                }
+            }
+        }
+        names.extend(args);
+
+        if clear {
+            self.path_dirs.clear_hashed();
+        }
+        if names.is_empty() {
+            if print {
+                return Err(ShellError::MissingOptionArgument("hash: -t".to_string()));
+            }
+            if path.is_some() {
+                return Err(ShellError::EmptyArgs(1));
+            }
+            if clear {
+                return Ok(CommandResult::Empty);
+            }
+            return Ok(self.list());
+        }
+
+        let mut stdout = String::new();
+        let mut stderr = String::new();
+
+        for name in &names {
+            if print {
+                match self.path_dirs.hashed(name) {
+                    // Several names are printed along with their paths
+                    Some(hashed) if names.len() > 1 => {
+                        stdout.push_str(&format!("{name}\t{}\n", hashed.path.display()))
+                    }
+                    Some(hashed) => stdout.push_str(&format!("{}\n", hashed.path.display())),
+                    None => {
+                        stderr.push_str(&format!("{}\n", HashError::NotFound(name.to_string())))
+                    }
+                }
+            } else if let Some(path) = &path {
+                self.path_dirs.set_hashed(name, path.clone());
+            } else if CommandToken::from_str(name).is_err()
+                && !name.contains('/')
+                && self.path_dirs.hash(name).is_none()
+            {
+                // Builtins and paths are never hashed
+                stderr.push_str(&format!("{}\n", HashError::NotFound(name.to_string())));
+            }
+        }
+
+        if stderr.is_empty() {
+            return Ok(CommandResult::stdout(stdout));
+        }
+        Ok(CommandResult::Failure(stdout, stderr, 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn setup() -> (Arc<PathDirsProvider>, Hash) {
+        let path_dirs = Arc::new(PathDirsProvider::new(vec![
+            PathBuf::from("/usr/bin"),
+            PathBuf::from("/bin"),
+        ]));
+        (Arc::clone(&path_dirs), Hash::new(path_dirs))
+    }
+
+    #[test]
+    fn empty_table() {
+        let (_, hash) = setup();
+        assert_eq!(
+            hash.execute(&[]),
+            Ok(CommandResult::stdout("hash: hash table empty\n"))
+        );
+    }
+
+    #[test]
+    fn hash_lists_and_forgets_commands() {
+        let (path_dirs, hash) = setup();
+        let ls = path_dirs.find_executable("ls").unwrap();
+        assert_eq!(hash.execute(&args(&["ls"])), Ok(CommandResult::stdout("")));
+        path_dirs.lookup("ls");
+
+        assert_eq!(
+            hash.execute(&[]),
+            Ok(CommandResult::stdout(format!(
+                "hits\tcommand\n   1\t{}\n",
+                ls.display()
+            )))
+        );
+        assert_eq!(hash.execute(&args(&["-r"])), Ok(CommandResult::Empty));
+        assert_eq!(path_dirs.hashed("ls"), None);
+    }
+
+    #[test]
+    fn set_and_print_paths() {
+        let (_, hash) = setup();
+        hash.execute(&args(&["-p", "/opt/tool", "tool", "other"]))
+            .unwrap();
+
+        assert_eq!(
+            hash.execute(&args(&["-t", "tool"])),
+            Ok(CommandResult::stdout("/opt/tool\n"))
+        );
+        assert_eq!(
+            hash.execute(&args(&["-t", "tool", "other"])),
+            Ok(CommandResult::stdout("tool\t/opt/tool\nother\t/opt/tool\n"))
+        );
+        assert_eq!(
+            hash.execute(&args(&["-t", "missing"])),
+            Ok(CommandResult::Failure(
+                String::new(),
+                "hash: missing: not found\n".to_string(),
+                1
+            ))
+        );
+    }
+
+    #[test]
+    fn unknown_commands_are_not_found() {
+        let (path_dirs, hash) = setup();
+        assert_eq!(
+            hash.execute(&args(&["echo", "nosuchcommand12345"])),
+            Ok(CommandResult::Failure(
+                String::new(),
+                "hash: nosuchcommand12345: not found\n".to_string(),
+                1
+            ))
+        );
+        assert_eq!(path_dirs.hashed_commands(), Vec::new());
+    }
+
+    #[test]
+    fn option_errors() {
+        let (_, hash) = setup();
+        assert_eq!(
+            hash.execute(&args(&["-x"])),
+            Err(ShellError::InvalidOption("hash: -x".to_string()))
+        );
+        assert_eq!(
+            hash.execute(&args(&["-p"])),
+            Err(ShellError::MissingOptionArgument("hash: -p".to_string()))
+        );
+        assert_eq!(
+            hash.execute(&args(&["-t"])),
+            Err(ShellError::MissingOptionArgument("hash: -t".to_string()))
+        );
+    }
+}
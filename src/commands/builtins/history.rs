@@ -0,0 +1,180 @@
+use std::{path::PathBuf, sync::Arc};
+
+use crate::{
+    exceptions::{commands::ShellError, history_error::HistoryError},
+    port::command::{Command, CommandResult},
+    shell::history::History,
+};
+
+pub struct HistoryCommand {
+    history: Arc<History>,
+}
+
+impl HistoryCommand {
+    pub fn new(history: Arc<History>) -> Self {
+        Self { history }
+    }
+
+    fn list(&self, count: Option<usize>) -> CommandResult {
+        let entries = self.history.entries();
+        let start = count.map_or(0, |count| entries.len().saturating_sub(count));
+
+        let listing: String = entries
+            .iter()
+            .enumerate()
+            .skip(start)
+            .map(|(idx, entry)| format!("{:5}  {entry}\n", idx + 1))
+            .collect();
+        CommandResult::stdout(listing)
+    }
+
+    /// Resolves a 1-based offset, negative offsets count from the end.
+    fn delete(&self, offset: &str) -> Result<CommandResult, ShellError> {
+        let out_of_range = || HistoryError::OutOfRange(offset.to_owned());
+        let position: i64 = offset.parse().map_err(|_| out_of_range())?;
+        let len = self.history.len() as i64;
+
+        let index = match position {
+            position if position > 0 => position - 1,
+            position if position < 0 => len + position,
+            _ => return Err(out_of_range().into()),
+        };
+
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| self.history.delete(index))
+            .ok_or_else(out_of_range)?;
+        Ok(CommandResult::Empty)
+    }
+
+    fn file(&self, path: Option<&String>) -> Result<PathBuf, ShellError> {
+        path.map(PathBuf::from)
+            .or_else(|| self.history.file().map(PathBuf::from))
+            .ok_or_else(|| HistoryError::Io("HISTFILE: parameter not set".to_string()).into())
+    }
+}
+
+impl Command for HistoryCommand {
+    fn execute(&self, args: &[String]) -> Result<CommandResult, ShellError> {
+        let mut args = args.iter();
+
+        match args.next().map(String::as_str) {
+            None => Ok(self.list(None)),
+            Some("-c") => {
+                self.history.clear();
+                Ok(CommandResult::Empty)
+            }
+            Some("-d") => match args.next() {
+                Some(offset) => self.delete(offset),
+                None => Err(ShellError::MissingOptionArgument("history: -d".to_string())),
+            },
+            Some("-a") => {
+                self.history.append_file(&self.file(args.next())?)?;
+                Ok(CommandResult::Empty)
+            }
+            Some("-w") => {
+                self.history.write_file(&self.file(args.next())?)?;
+                Ok(CommandResult::Empty)
+            }
+            Some("-r") => {
+                self.history.read_file(&self.file(args.next())?)?;
+                Ok(CommandResult::Empty)
+            }
+            Some(option) if option.starts_with('-') => {
+                Err(ShellError::InvalidOption(format!("history: {option}")))
+            }
+            Some(count) => {
+                if args.len() > 0 {
+                    return Err(ShellError::TooManyArguments(
+                        "at most 1".to_string(),
+                        args.len() + 1,
+                    ));
+                }
+                let count = count
+                    .parse()
+                    .map_err(|_| HistoryError::NumericArgument(count.to_owned()))?;
+                Ok(self.list(Some(count)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (Arc<History>, HistoryCommand) {
+        let history = Arc::new(History::default());
+        for line in ["echo one", "pwd", "echo two"] {
+            history.add(line);
+        }
+        (Arc::clone(&history), HistoryCommand::new(history))
+    }
+
+    #[test]
+    fn list_history() {
+        let (_, command) = setup();
+        assert_eq!(
+            command.execute(&[]),
+            Ok(CommandResult::stdout(
+                "    1  echo one\n    2  pwd\n    3  echo two\n"
+            ))
+        );
+        assert_eq!(
+            command.execute(&["1".to_string()]),
+            Ok(CommandResult::stdout("    3  echo two\n"))
+        );
+    }
+
+    #[test]
+    fn clear_history() {
+        let (history, command) = setup();
+        assert_eq!(
+            command.execute(&["-c".to_string()]),
+            Ok(CommandResult::Empty)
+        );
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn delete_entries() {
+        let (history, command) = setup();
+        command
+            .execute(&["-d".to_string(), "2".to_string()])
+            .unwrap();
+        command
+            .execute(&["-d".to_string(), "-1".to_string()])
+            .unwrap();
+        assert_eq!(history.entries(), vec!["echo one".to_string()]);
+
+        assert_eq!(
+            command.execute(&["-d".to_string(), "7".to_string()]),
+            Err(HistoryError::OutOfRange("7".to_string()).into())
+        );
+    }
+
+    #[test]
+    fn write_and_read_file() {
+        let (history, command) = setup();
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let path = file.path().display().to_string();
+
+        command.execute(&["-w".to_string(), path.clone()]).unwrap();
+        command.execute(&["-c".to_string()]).unwrap();
+        command.execute(&["-r".to_string(), path]).unwrap();
+        assert_eq!(history.len(), 3);
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        let (_, command) = setup();
+        assert_eq!(
+            command.execute(&["abc".to_string()]),
+            Err(HistoryError::NumericArgument("abc".to_string()).into())
+        );
+        assert_eq!(
+            command.execute(&["-z".to_string()]),
+            Err(ShellError::InvalidOption("history: -z".to_string()))
+        );
+    }
+}
@@ -1,5 +1,15 @@
+pub mod alias;
 pub mod cd;
+pub mod clear;
+pub mod compgen;
+pub mod complete;
 pub mod echo;
 pub mod exit;
+pub mod hash;
+pub mod history;
 pub mod pwd;
+pub mod r#return;
+pub mod set;
+pub mod source;
 pub mod r#type;
+pub mod unalias;
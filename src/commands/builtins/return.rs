@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use crate::{
+    exceptions::commands::ShellError,
+    port::command::{Command, CommandResult},
+    shell::parameters::ShellParameters,
+};
+
+pub struct Return {
+    parameters: Arc<ShellParameters>,
+}
+
+impl Return {
+    pub fn new(parameters: Arc<ShellParameters>) -> Self {
+        Self { parameters }
+    }
+}
+
+impl Command for Return {
+    fn execute(&self, args: &[String]) -> Result<CommandResult, ShellError> {
+        if args.len() > 1 {
+            return Err(ShellError::TooManyArguments(
+                "at most 1".to_string(),
+                args.len(),
+            ));
+        }
+
+        let code = match args.first() {
+            Some(code) => code
+                .parse()
+                .map_err(|_| ShellError::ParsingError("integer".to_string()))?,
+            None => self.parameters.last_status(),
+        };
+
+        Ok(CommandResult::Return(code))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn return_command() -> Return {
+        let parameters = Arc::new(ShellParameters::default());
+        parameters.set_last_status(3);
+        Return::new(parameters)
+    }
+
+    #[test]
+    fn return_with_code() {
+        let result = return_command().execute(&["5".to_string()]);
+        assert_eq!(result, Ok(CommandResult::Return(5)));
+    }
+
+    #[test]
+    fn return_defaults_to_last_status() {
+        let result = return_command().execute(&[]);
+        assert_eq!(result, Ok(CommandResult::Return(3)));
+    }
+
+    #[test]
+    fn return_with_invalid_code() {
+        let result = return_command().execute(&["abc".to_string()]);
+        assert_eq!(result, Err(ShellError::ParsingError("integer".to_string())));
+    }
+}
@@ -0,0 +1,202 @@
+use std::sync::Arc;
+
+use strum::IntoEnumIterator;
+
+use crate::{
+    exceptions::commands::ShellError,
+    port::command::{Command, CommandResult},
+    shell::{
+        options::{ShellOption, ShellOptions},
+        parameters::ShellParameters,
+    },
+};
+
+pub struct Set {
+    options: Arc<ShellOptions>,
+    parameters: Arc<ShellParameters>,
+}
+
+impl Set {
+    pub fn new(options: Arc<ShellOptions>, parameters: Arc<ShellParameters>) -> Self {
+        Self {
+            options,
+            parameters,
+        }
+    }
+
+    /// Startup-only options are rejected as `name`.
+    fn apply(&self, option: ShellOption, enable: bool, name: &str) -> Result<(), ShellError> {
+        if option.is_startup_only() {
+            return Err(ShellError::InvalidOption(format!("set: {name}")));
+        }
+
+        if enable {
+            self.options.enable(option)
+        } else {
+            self.options.disable(option)
+        }
+        Ok(())
+    }
+
+    /// `set -o` prints the state of every option, startup-only ones as
+    /// read-only. `set +o` prints the commands that would restore them.
+    fn list(&self, enable: bool) -> CommandResult {
+        let listing: String = ShellOption::iter()
+            .filter(|option| enable || !option.is_startup_only())
+            .map(|option| {
+                let enabled = self.options.is_enabled(option);
+                if enable {
+                    let read_only = if option.is_startup_only() {
+                        " (read-only)"
+                    } else {
+                        ""
+                    };
+                    format!(
+                        "{option:<15}\t{}{read_only}\n",
+                        if enabled { "on" } else { "off" }
+                    )
+                } else {
+                    format!("set {}o {option}\n", if enabled { '-' } else { '+' })
+                }
+            })
+            .collect();
+        CommandResult::stdout(listing)
+    }
+}
+
+impl Command for Set {
+    fn execute(&self, args: &[String]) -> Result<CommandResult, ShellError> {
+        if args.is_empty() {
+            return Ok(self.list(true));
+        }
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let enable = match arg.chars().next() {
+                Some('-') if arg == "--" => {
+                    self.parameters.replace_positional(args.cloned().collect());
+                    break;
+                }
+                Some('-') => true,
+                Some('+') => false,
+                _ => {
+                    let mut positional = vec![arg.clone()];
+                    positional.extend(args.cloned());
+                    self.parameters.replace_positional(positional);
+                    break;
+                }
+            };
+
+            if arg.len() == 2 && arg.ends_with('o') {
+                match args.next() {
+                    Some(name) => self.apply(name.parse()?, enable, name)?,
+                    None => return Ok(self.list(enable)),
+                }
+                continue;
+            }
+
+            for flag in arg.chars().skip(1) {
+                let option = ShellOption::from_flag(flag).ok_or_else(|| {
+                    ShellError::InvalidOption(format!("set: {}{flag}", &arg[..1]))
+                })?;
+                self.apply(option, enable, &format!("{}{flag}", &arg[..1]))?;
+            }
+        }
+
+        Ok(CommandResult::Empty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (Arc<ShellOptions>, Arc<ShellParameters>, Set) {
+        let options = Arc::new(ShellOptions::new([ShellOption::HistExpand]));
+        let parameters = Arc::new(ShellParameters::default());
+        let set = Set::new(Arc::clone(&options), Arc::clone(&parameters));
+        (options, parameters, set)
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn toggle_short_flags() {
+        let (options, _, set) = setup();
+        assert_eq!(set.execute(&args(&["+H", "-ex"])), Ok(CommandResult::Empty));
+        assert!(!options.is_enabled(ShellOption::HistExpand));
+        assert!(options.is_enabled(ShellOption::Errexit));
+        assert!(options.is_enabled(ShellOption::Xtrace));
+    }
+
+    #[test]
+    fn toggle_long_names() {
+        let (options, _, set) = setup();
+        set.execute(&args(&["-o", "xtrace", "+o", "histexpand"]))
+            .unwrap();
+        assert!(options.is_enabled(ShellOption::Xtrace));
+        assert!(!options.is_enabled(ShellOption::HistExpand));
+    }
+
+    #[test]
+    fn list_options() {
+        let (_, _, set) = setup();
+        let Ok(CommandResult::Stdio(stdout, _)) = set.execute(&args(&["-o"])) else {
+            panic!("expected a listing");
+        };
+        assert!(stdout.contains("errexit        \toff\n"));
+        assert!(stdout.contains("histexpand     \ton\n"));
+
+        let Ok(CommandResult::Stdio(stdout, _)) = set.execute(&args(&["+o"])) else {
+            panic!("expected a listing");
+        };
+        assert!(stdout.contains("set -o histexpand\n"));
+    }
+
+    #[test]
+    fn startup_options_are_read_only() {
+        let (options, _, set) = setup();
+        assert_eq!(
+            set.execute(&args(&["-o", "interactive"])),
+            Err(ShellError::InvalidOption("set: interactive".to_owned()))
+        );
+        assert_eq!(
+            set.execute(&args(&["-l"])),
+            Err(ShellError::InvalidOption("set: -l".to_owned()))
+        );
+        assert!(!options.is_enabled(ShellOption::Interactive));
+        assert!(!options.is_enabled(ShellOption::Login));
+
+        let Ok(CommandResult::Stdio(stdout, _)) = set.execute(&args(&["-o"])) else {
+            panic!("expected a listing");
+        };
+        assert!(stdout.contains("norc           \toff (read-only)\n"));
+
+        let Ok(CommandResult::Stdio(stdout, _)) = set.execute(&args(&["+o"])) else {
+            panic!("expected a listing");
+        };
+        assert!(!stdout.contains("interactive"));
+    }
+
+    #[test]
+    fn set_positional_parameters() {
+        let (_, parameters, set) = setup();
+        set.execute(&args(&["--", "a", "b"])).unwrap();
+        assert_eq!(parameters.positional(), args(&["a", "b"]));
+    }
+
+    #[test]
+    fn invalid_options() {
+        let (_, _, set) = setup();
+        assert_eq!(
+            set.execute(&args(&["-z"])),
+            Err(ShellError::InvalidOption("set: -z".to_string()))
+        );
+        assert_eq!(
+            set.execute(&args(&["-o", "nope"])),
+            Err(ShellError::InvalidOption("nope".to_string()))
+        );
+    }
+}
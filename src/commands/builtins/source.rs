@@ -0,0 +1,92 @@
+use std::{path::PathBuf, sync::Arc};
+
+use crate::{
+    exceptions::commands::ShellError,
+    port::command::{Command, CommandResult},
+    shell::path::PathDirsProvider,
+};
+
+pub struct Source {
+    path_dirs: Arc<PathDirsProvider>,
+}
+
+impl Source {
+    pub fn new(path_dirs: Arc<PathDirsProvider>) -> Self {
+        Self { path_dirs }
+    }
+
+    fn resolve(&self, file_name: &str) -> Result<PathBuf, ShellError> {
+        let path = PathBuf::from(file_name);
+
+        // Like POSIX `.`, names without a slash are looked up in PATH first
+        if !file_name.contains('/') {
+            if let Some(path) = self.path_dirs.find_file(file_name) {
+                return Ok(path);
+            }
+        }
+
+        if path.is_file() {
+            return Ok(path);
+        }
+
+        Err(ShellError::FileNotFound(path))
+    }
+}
+
+impl Command for Source {
+    fn execute(&self, args: &[String]) -> Result<CommandResult, ShellError> {
+        let file_name = args.first().ok_or(ShellError::EmptyArgs(1))?;
+        let path = self.resolve(file_name)?;
+
+        Ok(CommandResult::Source(path, args[1..].to_vec()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (tempfile::TempDir, Source) {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("script.sh"), "echo sourced\n").unwrap();
+        let path_dirs = Arc::new(PathDirsProvider::new(vec![dir.path().to_path_buf()]));
+        (dir, Source::new(path_dirs))
+    }
+
+    #[test]
+    fn source_reads_file_with_path() {
+        let (dir, source) = setup();
+        let path = dir.path().join("script.sh");
+        let result = source.execute(&[path.to_string_lossy().to_string()]);
+        assert_eq!(result, Ok(CommandResult::Source(path, vec![])));
+    }
+
+    #[test]
+    fn source_searches_path_for_bare_names() {
+        let (dir, source) = setup();
+        let result = source.execute(&["script.sh".to_string(), "arg".to_string()]);
+        assert_eq!(
+            result,
+            Ok(CommandResult::Source(
+                dir.path().join("script.sh"),
+                vec!["arg".to_string()]
+            ))
+        );
+    }
+
+    #[test]
+    fn source_missing_file() {
+        let (_dir, source) = setup();
+        let result = source.execute(&["missing.sh".to_string()]);
+        assert_eq!(
+            result,
+            Err(ShellError::FileNotFound(PathBuf::from("missing.sh")))
+        );
+    }
+
+    #[test]
+    fn source_without_args() {
+        let (_dir, source) = setup();
+        assert_eq!(source.execute(&[]), Err(ShellError::EmptyArgs(1)));
+    }
+}
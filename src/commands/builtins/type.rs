@@ -7,16 +7,25 @@ use crate::{
         type_command_error::TypeCommandError,
     },
     port::command::{Command, CommandResult},
-    shell::path::PathDirsProvider,
+    shell::{alias::AliasTable, path::PathDirsProvider},
 };
 
 pub struct Type {
     path_dirs: Arc<PathDirsProvider>,
+    aliases: Arc<AliasTable>,
 }
 
 impl Type {
     pub fn new(path_dirs: Arc<PathDirsProvider>) -> Self {
-        Self { path_dirs }
+        Self {
+            path_dirs,
+            aliases: Arc::new(AliasTable::default()),
+        }
+    }
+
+    pub fn with_aliases(mut self, aliases: Arc<AliasTable>) -> Self {
+        self.aliases = aliases;
+        self
     }
 }
 
@@ -34,6 +43,12 @@ impl Command for Type {
         }
         let arg = &args[0];
 
+        if let Some(value) = self.aliases.get(arg) {
+            return Ok(CommandResult::stdout(format!(
+                "{arg} is aliased to `{value}'\n"
+            )));
+        }
+
         if CommandToken::from_str(arg).is_ok() {
             return Ok(CommandResult::stdout(format!(
                 "{} is a shell builtin\n",
@@ -41,6 +56,13 @@ impl Command for Type {
             )));
         }
 
+        if let Some(hashed) = self.path_dirs.hashed(arg) {
+            return Ok(CommandResult::stdout(format!(
+                "{arg} is hashed ({})\n",
+                hashed.path.display()
+            )));
+        }
+
         match self.path_dirs.find_executable(arg) {
             Some(exe_path) => Ok(CommandResult::stdout(format!(
                 "{arg} is {}\n",
@@ -225,4 +247,17 @@ mod tests {
         let result = Type::new(paths).execute(&["ls".to_string()]);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn type_alias() {
+        let aliases = Arc::new(AliasTable::default());
+        aliases.set("ll", "ls -la");
+        let result = Type::new(create_empty_path())
+            .with_aliases(aliases)
+            .execute(&["ll".to_string()]);
+        assert_eq!(
+            result,
+            Ok(CommandResult::stdout("ll is aliased to `ls -la'\n"))
+        )
+    }
 }
@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use crate::{
+    exceptions::{alias_error::AliasError, commands::ShellError},
+    port::command::{Command, CommandResult},
+    shell::alias::AliasTable,
+};
+
+pub struct Unalias {
+    aliases: Arc<AliasTable>,
+}
+
+impl Unalias {
+    pub fn new(aliases: Arc<AliasTable>) -> Self {
+        Self { aliases }
+    }
+}
+
+impl Command for Unalias {
+    fn execute(&self, args: &[String]) -> Result<CommandResult, ShellError> {
+        if args.is_empty() {
+            return Err(ShellError::EmptyArgs(1));
+        }
+
+        if args.iter().any(|arg| arg == "-a") {
+            self.aliases.clear();
+            return Ok(CommandResult::Empty);
+        }
+
+        let stderr: String = args
+            .iter()
+            .filter(|name| self.aliases.remove(name).is_none())
+            .map(|name| format!("{}\n", AliasError::UnaliasNotFound(name.clone())))
+            .collect();
+
+        if stderr.is_empty() {
+            return Ok(CommandResult::Empty);
+        }
+        Ok(CommandResult::Failure(String::new(), stderr, 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (Arc<AliasTable>, Unalias) {
+        let aliases = Arc::new(AliasTable::default());
+        aliases.set("ll", "ls -la");
+        aliases.set("la", "ls -A");
+        (Arc::clone(&aliases), Unalias::new(aliases))
+    }
+
+    #[test]
+    fn remove_alias() {
+        let (aliases, unalias) = setup();
+        let result = unalias.execute(&["ll".to_string()]);
+        assert_eq!(result, Ok(CommandResult::Empty));
+        assert_eq!(aliases.names(), vec!["la".to_string()]);
+    }
+
+    #[test]
+    fn remove_all_aliases() {
+        let (aliases, unalias) = setup();
+        let result = unalias.execute(&["-a".to_string()]);
+        assert_eq!(result, Ok(CommandResult::Empty));
+        assert!(aliases.names().is_empty());
+    }
+
+    #[test]
+    fn remove_unknown_alias_fails() {
+        let (_, unalias) = setup();
+        let result = unalias.execute(&["nope".to_string()]);
+        assert_eq!(
+            result,
+            Ok(CommandResult::Failure(
+                String::new(),
+                "unalias: nope: not found\n".to_string(),
+                1
+            ))
+        );
+    }
+
+    #[test]
+    fn unalias_without_args() {
+        let (_, unalias) = setup();
+        assert_eq!(unalias.execute(&[]), Err(ShellError::EmptyArgs(1)));
+    }
+}
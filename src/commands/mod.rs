@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use std::{fmt::Display, str::FromStr};
 
 use strum::IntoEnumIterator;
 
@@ -14,6 +14,16 @@ pub(crate) enum CommandToken {
     Type,
     Pwd,
     Cd,
+    Source,
+    Return,
+    Alias,
+    Unalias,
+    History,
+    Set,
+    Clear,
+    Complete,
+    Compgen,
+    Hash,
 }
 
 impl FromStr for CommandToken {
@@ -25,21 +35,41 @@ impl FromStr for CommandToken {
             "type" => Ok(Self::Type),
             "pwd" => Ok(Self::Pwd),
             "cd" => Ok(Self::Cd),
+            "source" | "." => Ok(Self::Source),
+            "return" => Ok(Self::Return),
+            "alias" => Ok(Self::Alias),
+            "unalias" => Ok(Self::Unalias),
+            "history" => Ok(Self::History),
+            "set" => Ok(Self::Set),
+            "clear" => Ok(Self::Clear),
+            "complete" => Ok(Self::Complete),
+            "compgen" => Ok(Self::Compgen),
+            "hash" => Ok(Self::Hash),
             _ => Err(ShellError::CommandNotFound(command.to_owned())),
         }
     }
 }
 
-impl ToString for CommandToken {
-    fn to_string(&self) -> String {
-        match self {
+impl Display for CommandToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let token = match self {
             CommandToken::Echo => "echo",
             CommandToken::Cd => "cd",
             CommandToken::Pwd => "pwd",
             CommandToken::Type => "type",
             CommandToken::Exit => "exit",
-        }
-        .to_owned()
+            CommandToken::Source => "source",
+            CommandToken::Return => "return",
+            CommandToken::Alias => "alias",
+            CommandToken::Unalias => "unalias",
+            CommandToken::History => "history",
+            CommandToken::Set => "set",
+            CommandToken::Clear => "clear",
+            CommandToken::Complete => "complete",
+            CommandToken::Compgen => "compgen",
+            CommandToken::Hash => "hash",
+        };
+        f.write_str(token)
     }
 }
 
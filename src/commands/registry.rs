@@ -7,20 +7,17 @@ use crate::{
         command::{Command, CommandResult},
         shell_component::ShellComponent,
     },
-    shell::path::PathDirsProvider,
 };
 
 pub(crate) struct CommandRegistry {
     registry: HashMap<CommandToken, Arc<dyn Command>>,
-    path_dirs: Arc<PathDirsProvider>,
     next: Arc<dyn ShellComponent>,
 }
 
 impl CommandRegistry {
-    pub(crate) fn new(path_dirs: Arc<PathDirsProvider>, next: Arc<dyn ShellComponent>) -> Self {
+    pub(crate) fn new(next: Arc<dyn ShellComponent>) -> Self {
         Self {
             registry: HashMap::default(),
-            path_dirs,
             next,
         }
     }
@@ -51,7 +48,9 @@ impl ShellComponent for CommandRegistry {
 }
 #[cfg(test)]
 mod tests {
-    use crate::{external::ExternalCommand, port::command::CommandResult};
+    use crate::{
+        external::ExternalCommand, port::command::CommandResult, shell::path::PathDirsProvider,
+    };
 
     use super::*;
 
@@ -65,8 +64,8 @@ mod tests {
     #[test]
     fn get_command() {
         let paths = Arc::new(PathDirsProvider::new(vec![]));
-        let external = Arc::new(ExternalCommand::new(paths.clone()));
-        let mut registry = CommandRegistry::new(paths, external);
+        let external = Arc::new(ExternalCommand::new(paths));
+        let mut registry = CommandRegistry::new(external);
         registry.register(CommandToken::Exit, Arc::new(FakeCommand));
         let result = registry.try_get("exit");
         assert!(result.is_ok())
@@ -75,8 +74,8 @@ mod tests {
     #[test]
     fn command_not_found() {
         let paths = Arc::new(PathDirsProvider::new(vec![]));
-        let external = Arc::new(ExternalCommand::new(paths.clone()));
-        let registry = CommandRegistry::new(paths, external);
+        let external = Arc::new(ExternalCommand::new(paths));
+        let registry = CommandRegistry::new(external);
         let result = registry.try_get("exit");
         assert!(result.is_err());
         assert_eq!(
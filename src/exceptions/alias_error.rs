@@ -0,0 +1,9 @@
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum AliasError {
+    #[error("alias: {0}: not found")]
+    NotFound(String),
+    #[error("unalias: {0}: not found")]
+    UnaliasNotFound(String),
+    #[error("alias: `{0}': invalid alias name")]
+    InvalidName(String),
+}
@@ -10,6 +10,14 @@ pub enum ShellError {
     ParsingError(String),
     #[error(transparent)]
     TypeCommandError(#[from] super::type_command_error::TypeCommandError),
+    #[error(transparent)]
+    AliasError(#[from] super::alias_error::AliasError),
+    #[error(transparent)]
+    CompletionError(#[from] super::completion_error::CompletionError),
+    #[error(transparent)]
+    HashError(#[from] super::hash_error::HashError),
+    #[error(transparent)]
+    HistoryError(#[from] super::history_error::HistoryError),
     #[error("No args received expected at least: {0}")]
     EmptyArgs(usize),
     #[error("{0}")]
@@ -18,8 +26,41 @@ pub enum ShellError {
     Uncontroled(String),
     #[error("{0}: No such file or directory")]
     DirectoryNotFound(PathBuf),
+    #[error("{0}: No such file or directory")]
+    FileNotFound(PathBuf),
+    #[error("{0}: {1}")]
+    FileError(PathBuf, String),
     #[error("{0}: is not a directory")]
     NotADirectory(PathBuf),
     #[error("Missing closing single quote")]
     MissingClosingQuote,
+    #[error("{0}: invalid option")]
+    InvalidOption(String),
+    #[error("{0}: option requires an argument")]
+    MissingOptionArgument(String),
+    #[error("syntax error near unexpected token `{0}'")]
+    SyntaxError(String),
+    #[error("syntax error: unexpected end of file")]
+    IncompleteInput,
+    #[error("return: can only `return' from a sourced script")]
+    ReturnOutsideScript,
+    #[error("{0}: maximum source nesting exceeded")]
+    SourceNestingExceeded(PathBuf),
+    #[error("{0}: line {1}: {2}")]
+    AtLine(String, usize, Box<ShellError>),
+}
+
+impl ShellError {
+    pub fn exit_status(&self) -> i32 {
+        match self {
+            ShellError::CommandNotFound(_) => 127,
+            ShellError::AtLine(_, _, error) => error.exit_status(),
+            ShellError::InvalidOption(_)
+            | ShellError::MissingOptionArgument(_)
+            | ShellError::SyntaxError(_)
+            | ShellError::IncompleteInput
+            | ShellError::MissingClosingQuote => 2,
+            _ => 1,
+        }
+    }
 }
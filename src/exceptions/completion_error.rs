@@ -0,0 +1,7 @@
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum CompletionError {
+    #[error("complete: {0}: no completion specification")]
+    NoSpecification(String),
+    #[error("{0}: -F: shell functions are not supported, use -C")]
+    FunctionUnsupported(String),
+}
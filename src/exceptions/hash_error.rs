@@ -0,0 +1,5 @@
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum HashError {
+    #[error("hash: {0}: not found")]
+    NotFound(String),
+}
@@ -0,0 +1,15 @@
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum HistoryError {
+    #[error("history: {0}: history position out of range")]
+    OutOfRange(String),
+    #[error("history: {0}: numeric argument required")]
+    NumericArgument(String),
+    #[error("{0}: event not found")]
+    EventNotFound(String),
+    #[error("{0}: bad word specifier")]
+    BadWordSpecifier(String),
+    #[error("{0}: substitution failed")]
+    SubstitutionFailed(String),
+    #[error("history: {0}")]
+    Io(String),
+}
@@ -1,2 +1,6 @@
+pub(crate) mod alias_error;
 pub(crate) mod commands;
+pub(crate) mod completion_error;
+pub(crate) mod hash_error;
+pub(crate) mod history_error;
 pub(crate) mod type_command_error;
@@ -0,0 +1,243 @@
+use std::path::PathBuf;
+
+use crate::{exceptions::commands::ShellError, shell::options::ShellOption};
+
+const USAGE: &str = "\
+Usage: shell [option ...] [script-file [arg ...]]
+       shell [option ...] -c command-string [name [arg ...]]
+       shell [option ...] -s [arg ...]
+
+Options:
+  -c          read commands from the first operand
+  -s          read commands from standard input
+  -i          force the shell to be interactive
+  -l          act as a login shell
+  -e          exit immediately when a command fails (errexit)
+  -x          print commands before executing them (xtrace)
+  --norc      do not read the startup file
+  --version   print version information and exit
+  --help      print this help and exit
+";
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum ShellMode {
+    Stdin,
+    Command(String),
+    Script(PathBuf),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum CliAction {
+    Run,
+    Help,
+    Version,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct CliArgs {
+    action: CliAction,
+    mode: ShellMode,
+    options: Vec<ShellOption>,
+    name: String,
+    positional: Vec<String>,
+}
+
+impl CliArgs {
+    pub(crate) fn usage() -> &'static str {
+        USAGE
+    }
+
+    pub(crate) fn version() -> String {
+        format!("{} {}\n", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+    }
+
+    pub(crate) fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, ShellError> {
+        let mut args = args.into_iter();
+        let program = args
+            .next()
+            .unwrap_or_else(|| env!("CARGO_PKG_NAME").to_owned());
+
+        let mut action = CliAction::Run;
+        let mut options = Vec::new();
+        let mut command_mode = false;
+        let mut stdin_mode = false;
+        let mut operands = Vec::new();
+
+        for arg in args.by_ref() {
+            match arg.as_str() {
+                "--" | "-" => break,
+                "--help" => action = CliAction::Help,
+                "--version" => action = CliAction::Version,
+                "--norc" => options.push(ShellOption::NoRc),
+                "--login" => options.push(ShellOption::Login),
+                long if long.starts_with("--") => {
+                    return Err(ShellError::InvalidOption(long.to_owned()))
+                }
+                short if short.starts_with('-') => {
+                    for flag in short.chars().skip(1) {
+                        match flag {
+                            'c' => command_mode = true,
+                            's' => stdin_mode = true,
+                            _ => options.push(
+                                ShellOption::from_flag(flag)
+                                    .ok_or(ShellError::InvalidOption(format!("-{flag}")))?,
+                            ),
+                        }
+                    }
+                }
+                _ => {
+                    operands.push(arg);
+                    break;
+                }
+            }
+        }
+        operands.extend(args);
+
+        let mut operands = operands.into_iter();
+        let (mode, name) = if command_mode {
+            let command = operands
+                .next()
+                .ok_or(ShellError::MissingOptionArgument("-c".to_owned()))?;
+            (
+                ShellMode::Command(command),
+                operands.next().unwrap_or(program),
+            )
+        } else if stdin_mode {
+            (ShellMode::Stdin, program)
+        } else {
+            match operands.next() {
+                Some(script) => (ShellMode::Script(PathBuf::from(&script)), script),
+                None => (ShellMode::Stdin, program),
+            }
+        };
+
+        Ok(Self {
+            action,
+            mode,
+            options,
+            name,
+            positional: operands.collect(),
+        })
+    }
+
+    pub(crate) fn action(&self) -> &CliAction {
+        &self.action
+    }
+
+    pub(crate) fn mode(&self) -> &ShellMode {
+        &self.mode
+    }
+
+    pub(crate) fn options(&self) -> &[ShellOption] {
+        &self.options
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn positional(&self) -> &[String] {
+        &self.positional
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliArgs, ShellError> {
+        CliArgs::parse(
+            std::iter::once("shell")
+                .chain(args.iter().copied())
+                .map(str::to_owned),
+        )
+    }
+
+    #[test]
+    fn no_args_reads_stdin() {
+        let cli = parse(&[]).unwrap();
+        assert_eq!(cli.action(), &CliAction::Run);
+        assert_eq!(cli.mode(), &ShellMode::Stdin);
+        assert_eq!(cli.name(), "shell");
+        assert!(cli.options().is_empty());
+        assert!(cli.positional().is_empty());
+    }
+
+    #[test]
+    fn command_string_with_name_and_args() {
+        let cli = parse(&["-c", "echo a; echo b", "myname", "one", "two"]).unwrap();
+        assert_eq!(cli.mode(), &ShellMode::Command("echo a; echo b".to_owned()));
+        assert_eq!(cli.name(), "myname");
+        assert_eq!(cli.positional(), &["one", "two"]);
+    }
+
+    #[test]
+    fn command_string_without_operand_is_an_error() {
+        assert_eq!(
+            parse(&["-c"]).unwrap_err(),
+            ShellError::MissingOptionArgument("-c".to_owned())
+        );
+    }
+
+    #[test]
+    fn combined_short_flags() {
+        let cli = parse(&["-ex", "-c", "true"]).unwrap();
+        assert_eq!(cli.options(), &[ShellOption::Errexit, ShellOption::Xtrace]);
+        assert_eq!(cli.mode(), &ShellMode::Command("true".to_owned()));
+
+        let cli = parse(&["-lc", "true"]).unwrap();
+        assert_eq!(cli.options(), &[ShellOption::Login]);
+        assert_eq!(cli.mode(), &ShellMode::Command("true".to_owned()));
+    }
+
+    #[test]
+    fn stdin_mode_keeps_operands_as_positional() {
+        let cli = parse(&["-s", "a", "b"]).unwrap();
+        assert_eq!(cli.mode(), &ShellMode::Stdin);
+        assert_eq!(cli.positional(), &["a", "b"]);
+    }
+
+    #[test]
+    fn script_operand() {
+        let cli = parse(&["-x", "script.sh", "arg"]).unwrap();
+        assert_eq!(cli.mode(), &ShellMode::Script(PathBuf::from("script.sh")));
+        assert_eq!(cli.name(), "script.sh");
+        assert_eq!(cli.positional(), &["arg"]);
+        assert_eq!(cli.options(), &[ShellOption::Xtrace]);
+    }
+
+    #[test]
+    fn options_stop_at_first_operand() {
+        let cli = parse(&["script.sh", "-x"]).unwrap();
+        assert!(cli.options().is_empty());
+        assert_eq!(cli.positional(), &["-x"]);
+    }
+
+    #[test]
+    fn double_dash_ends_options() {
+        let cli = parse(&["--", "-x"]).unwrap();
+        assert_eq!(cli.mode(), &ShellMode::Script(PathBuf::from("-x")));
+    }
+
+    #[test]
+    fn long_options() {
+        assert_eq!(parse(&["--help"]).unwrap().action(), &CliAction::Help);
+        assert_eq!(parse(&["--version"]).unwrap().action(), &CliAction::Version);
+        assert_eq!(
+            parse(&["--norc", "-i"]).unwrap().options(),
+            &[ShellOption::NoRc, ShellOption::Interactive]
+        );
+    }
+
+    #[test]
+    fn invalid_options() {
+        assert_eq!(
+            parse(&["-z"]).unwrap_err(),
+            ShellError::InvalidOption("-z".to_owned())
+        );
+        assert_eq!(
+            parse(&["--nope"]).unwrap_err(),
+            ShellError::InvalidOption("--nope".to_owned())
+        );
+    }
+}
@@ -0,0 +1,506 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashSet,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+};
+
+use crate::{
+    commands::{
+        builtins::{
+            alias::Alias, cd::Cd, clear::Clear, compgen::Compgen, complete::Complete, echo::Echo,
+            exit::Exit, hash::Hash, history::HistoryCommand, pwd::Pwd, r#return::Return,
+            r#type::Type, set::Set, source::Source, unalias::Unalias,
+        },
+        registry::CommandRegistry,
+        CommandToken,
+    },
+    exceptions::commands::ShellError,
+    external::ExternalCommand,
+    port::{command::CommandResult, shell_component::ShellComponent},
+    shell::{
+        alias::AliasTable,
+        completion::spec::CompletionSpecs,
+        file::FileManager,
+        history::History,
+        input::{
+            command_list::{CommandList, ListOperator},
+            input_parser::{InputParser, ParsedCommand},
+        },
+        options::{ShellOption, ShellOptions},
+        output_handler::OutputHandler,
+        parameters::ShellParameters,
+        path::PathDirsProvider,
+    },
+};
+
+/// How deep `source` may nest, so a file sourcing itself stops with an
+/// error instead of overflowing the stack.
+const MAX_SOURCE_DEPTH: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ExecutionFlow {
+    Continue,
+    Exit(i32),
+    Return(i32),
+}
+
+#[derive(Debug)]
+struct ScriptLocation {
+    name: String,
+    line: usize,
+}
+
+pub(crate) struct Executor {
+    builtins: CommandRegistry,
+    input_parser: InputParser,
+    output_handler: Arc<OutputHandler>,
+    path_dirs: Arc<PathDirsProvider>,
+    aliases: Arc<AliasTable>,
+    completion_specs: Arc<CompletionSpecs>,
+    history: Arc<History>,
+    options: Arc<ShellOptions>,
+    parameters: Arc<ShellParameters>,
+    source_depth: Cell<usize>,
+    locations: RefCell<Vec<ScriptLocation>>,
+}
+
+impl Executor {
+    pub(crate) fn new(
+        file_manager: Arc<FileManager>,
+        output_handler: Arc<OutputHandler>,
+        options: Arc<ShellOptions>,
+        parameters: Arc<ShellParameters>,
+    ) -> Self {
+        let path_dirs = Arc::new(PathDirsProvider::from_env());
+        let aliases = Arc::new(AliasTable::default());
+        let completion_specs = Arc::new(CompletionSpecs::default());
+        let history = Arc::new(History::from_env());
+        let external_command = Arc::new(ExternalCommand::new(Arc::clone(&path_dirs)));
+
+        let mut registry = CommandRegistry::new(external_command);
+        registry.register(CommandToken::Exit, Arc::new(Exit));
+        registry.register(CommandToken::Echo, Arc::new(Echo));
+        registry.register(
+            CommandToken::Type,
+            Arc::new(Type::new(Arc::clone(&path_dirs)).with_aliases(Arc::clone(&aliases))),
+        );
+        registry.register(CommandToken::Pwd, Arc::new(Pwd));
+        registry.register(
+            CommandToken::Cd,
+            Arc::new(Cd::new(Arc::clone(&file_manager))),
+        );
+        registry.register(
+            CommandToken::Source,
+            Arc::new(Source::new(Arc::clone(&path_dirs))),
+        );
+        registry.register(
+            CommandToken::Return,
+            Arc::new(Return::new(Arc::clone(&parameters))),
+        );
+        registry.register(
+            CommandToken::Alias,
+            Arc::new(Alias::new(Arc::clone(&aliases))),
+        );
+        registry.register(
+            CommandToken::Unalias,
+            Arc::new(Unalias::new(Arc::clone(&aliases))),
+        );
+        registry.register(
+            CommandToken::History,
+            Arc::new(HistoryCommand::new(Arc::clone(&history))),
+        );
+        registry.register(
+            CommandToken::Set,
+            Arc::new(Set::new(Arc::clone(&options), Arc::clone(&parameters))),
+        );
+        registry.register(CommandToken::Clear, Arc::new(Clear));
+        registry.register(
+            CommandToken::Complete,
+            Arc::new(Complete::new(Arc::clone(&completion_specs))),
+        );
+        registry.register(
+            CommandToken::Compgen,
+            Arc::new(Compgen::new(Arc::clone(&path_dirs))),
+        );
+        registry.register(
+            CommandToken::Hash,
+            Arc::new(Hash::new(Arc::clone(&path_dirs))),
+        );
+
+        Self {
+            builtins: registry,
+            input_parser: InputParser::new(file_manager).with_aliases(Arc::clone(&aliases)),
+            output_handler,
+            path_dirs,
+            aliases,
+            completion_specs,
+            history,
+            options,
+            parameters,
+            source_depth: Cell::new(0),
+            locations: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub(crate) fn path_dirs(&self) -> Arc<PathDirsProvider> {
+        Arc::clone(&self.path_dirs)
+    }
+
+    pub(crate) fn aliases(&self) -> Arc<AliasTable> {
+        Arc::clone(&self.aliases)
+    }
+
+    pub(crate) fn completion_specs(&self) -> Arc<CompletionSpecs> {
+        Arc::clone(&self.completion_specs)
+    }
+
+    pub(crate) fn history(&self) -> Arc<History> {
+        Arc::clone(&self.history)
+    }
+
+    pub(crate) fn is_interactive(&self) -> bool {
+        self.options.is_enabled(ShellOption::Interactive)
+    }
+
+    pub(crate) fn options(&self) -> Arc<ShellOptions> {
+        Arc::clone(&self.options)
+    }
+
+    pub(crate) fn output_handler(&self) -> Arc<OutputHandler> {
+        Arc::clone(&self.output_handler)
+    }
+
+    pub(crate) fn last_status(&self) -> i32 {
+        self.parameters.last_status()
+    }
+
+    /// Runs `$(command)` of a prompt in a child shell and returns its
+    /// stdout. The child gets the aliases, and nothing it does changes this
+    /// shell.
+    pub(crate) fn capture(&self, command: &str) -> Result<String, ShellError> {
+        let aliases: String = self
+            .aliases
+            .list()
+            .iter()
+            .map(|(name, value)| AliasTable::definition(name, value))
+            .collect();
+        let shell =
+            std::env::current_exe().map_err(|err| ShellError::ExternalError(err.to_string()))?;
+
+        let output = std::process::Command::new(shell)
+            .arg("-c")
+            .arg(format!("{aliases}{command}"))
+            .stdin(Stdio::null())
+            .stderr(Stdio::inherit())
+            .output()
+            .map_err(|err| ShellError::ExternalError(err.to_string()))?;
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    pub(crate) fn execute(&self, input: &str) -> Result<ExecutionFlow, ShellError> {
+        self.execute_list(input, None)
+    }
+
+    /// Runs a command list. A list that an alias expanded to comes with the
+    /// aliases it was expanded from, and its first command is expanded
+    /// already.
+    fn execute_list(
+        &self,
+        input: &str,
+        aliases: Option<&HashSet<String>>,
+    ) -> Result<ExecutionFlow, ShellError> {
+        let command_list = match CommandList::parse(input) {
+            Ok(command_list) => command_list,
+            Err(err) => return self.report(err),
+        };
+
+        let mut skip_next = false;
+        for (idx, item) in command_list.iter().enumerate() {
+            if aliases.is_none() {
+                if let Some(location) = self.locations.borrow_mut().last_mut() {
+                    location.line = item.line();
+                }
+            }
+
+            if !skip_next {
+                // An alias value may itself be a list, so it is parsed again,
+                // leaving alone the aliases it came from
+                let mut expanding = aliases.cloned().unwrap_or_default();
+                let expanded = (aliases.is_none() || idx > 0)
+                    .then(|| {
+                        self.input_parser
+                            .expand_aliases(item.command(), &mut expanding)
+                    })
+                    .filter(|expanded| expanded != item.command());
+                let flow = match expanded {
+                    Some(expanded) => self.execute_list(&expanded, Some(&expanding))?,
+                    None => self.execute_simple(item.command())?,
+                };
+                match flow {
+                    ExecutionFlow::Continue => {}
+                    flow => return Ok(flow),
+                }
+
+                // errexit ignores failures of commands that are followed by && or ||
+                let status = self.last_status();
+                if status != 0
+                    && item.operator() == ListOperator::Sequence
+                    && self.options.is_enabled(ShellOption::Errexit)
+                {
+                    return Ok(ExecutionFlow::Exit(status));
+                }
+            }
+
+            skip_next = match item.operator() {
+                ListOperator::Sequence => false,
+                ListOperator::And => self.last_status() != 0,
+                ListOperator::Or => self.last_status() == 0,
+            };
+        }
+
+        Ok(ExecutionFlow::Continue)
+    }
+
+    pub(crate) fn execute_script(&self, script: &str) -> Result<i32, ShellError> {
+        match self.execute(script)? {
+            ExecutionFlow::Exit(code) => Ok(code),
+            ExecutionFlow::Continue | ExecutionFlow::Return(_) => Ok(self.last_status()),
+        }
+    }
+
+    pub(crate) fn execute_file(&self, path: &Path) -> Result<i32, ShellError> {
+        let script = match std::fs::read_to_string(path) {
+            Ok(script) => script,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                self.report(ShellError::FileNotFound(path.to_path_buf()))?;
+                return Ok(127);
+            }
+            Err(err) => {
+                // The OS message without its "(os error N)" suffix
+                let message = err.to_string();
+                let message = message.split(" (os error").next().unwrap_or_default();
+                self.report(ShellError::FileError(
+                    path.to_path_buf(),
+                    message.to_owned(),
+                ))?;
+                return Ok(126);
+            }
+        };
+
+        match self.execute_located(path, &script)? {
+            ExecutionFlow::Exit(code) => Ok(code),
+            ExecutionFlow::Continue | ExecutionFlow::Return(_) => Ok(self.last_status()),
+        }
+    }
+
+    /// Sources the profile files for login shells and the rc file for
+    /// interactive ones. Errors are reported but never abort startup.
+    pub(crate) fn load_startup_files(&self) -> Result<ExecutionFlow, ShellError> {
+        let mut startup_files = Vec::new();
+        let home_dir = std::env::home_dir();
+
+        if self.options.is_enabled(ShellOption::Login) {
+            startup_files.push(PathBuf::from("/etc/profile"));
+            startup_files.extend(home_dir.as_ref().map(|home| home.join(".profile")));
+        }
+
+        if self.options.is_enabled(ShellOption::Interactive)
+            && !self.options.is_enabled(ShellOption::NoRc)
+        {
+            let rc_file = std::env::var_os("SHELLRC")
+                .map(PathBuf::from)
+                .or_else(|| home_dir.map(|home| home.join(".shellrc")));
+            startup_files.extend(rc_file);
+        }
+
+        for startup_file in startup_files.iter().filter(|file| file.is_file()) {
+            if let ExecutionFlow::Exit(code) = self.source(startup_file, vec![])? {
+                return Ok(ExecutionFlow::Exit(code));
+            }
+        }
+
+        Ok(ExecutionFlow::Continue)
+    }
+
+    fn execute_simple(&self, command: &str) -> Result<ExecutionFlow, ShellError> {
+        let (parsed_command, redirection) = match self.input_parser.parse(command) {
+            Ok(parsed) => parsed,
+            Err(err) => return self.report(err),
+        };
+
+        if self.options.is_enabled(ShellOption::Xtrace) {
+            self.trace(&parsed_command);
+        }
+
+        let result = match self.builtins.execute(parsed_command) {
+            Ok(result) => result,
+            Err(err) => CommandResult::Error(self.locate(err)),
+        };
+
+        match result {
+            CommandResult::Exit(code) => return Ok(ExecutionFlow::Exit(code)),
+            CommandResult::Source(path, args) => return self.source(&path, args),
+            CommandResult::Return(code) if self.source_depth.get() > 0 => {
+                self.parameters.set_last_status(code);
+                return Ok(ExecutionFlow::Return(code));
+            }
+            CommandResult::Return(_) => return self.report(ShellError::ReturnOutsideScript),
+            _ => {}
+        }
+
+        self.parameters.set_last_status(result.exit_status());
+        self.output_handler.handle(result, redirection)?;
+
+        Ok(ExecutionFlow::Continue)
+    }
+
+    fn execute_located(&self, path: &Path, script: &str) -> Result<ExecutionFlow, ShellError> {
+        self.locations.borrow_mut().push(ScriptLocation {
+            name: path.display().to_string(),
+            line: 1,
+        });
+        let flow = self.execute(script);
+        self.locations.borrow_mut().pop();
+        flow
+    }
+
+    fn source(&self, path: &Path, args: Vec<String>) -> Result<ExecutionFlow, ShellError> {
+        let script = match std::fs::read_to_string(path) {
+            Ok(script) => script,
+            Err(err) => {
+                return self.report(ShellError::Uncontroled(format!(
+                    "{}: {err}",
+                    path.display()
+                )))
+            }
+        };
+
+        if self.source_depth.get() >= MAX_SOURCE_DEPTH {
+            return self.report(ShellError::SourceNestingExceeded(path.to_path_buf()));
+        }
+
+        // Positional parameters are only overridden when arguments are given
+        let saved_positional = (!args.is_empty()).then(|| self.parameters.replace_positional(args));
+
+        self.source_depth.set(self.source_depth.get() + 1);
+        let flow = self.execute_located(path, &script);
+        self.source_depth.set(self.source_depth.get() - 1);
+
+        if let Some(positional) = saved_positional {
+            self.parameters.replace_positional(positional);
+        }
+
+        match flow? {
+            ExecutionFlow::Return(code) => {
+                self.parameters.set_last_status(code);
+                Ok(ExecutionFlow::Continue)
+            }
+            flow => Ok(flow),
+        }
+    }
+
+    fn trace(&self, parsed_command: &ParsedCommand) {
+        let mut line = vec![parsed_command.command()];
+        line.extend(parsed_command.args().iter().map(String::as_str));
+        eprintln!("+ {}", line.join(" "));
+    }
+
+    fn locate(&self, error: ShellError) -> ShellError {
+        match self.locations.borrow().last() {
+            Some(location) => {
+                ShellError::AtLine(location.name.clone(), location.line, Box::new(error))
+            }
+            None => error,
+        }
+    }
+
+    fn report(&self, error: ShellError) -> Result<ExecutionFlow, ShellError> {
+        let error = self.locate(error);
+        self.parameters.set_last_status(error.exit_status());
+        self.output_handler
+            .handle(CommandResult::Error(error), None)?;
+        Ok(ExecutionFlow::Continue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn executor() -> (Executor, Arc<ShellParameters>) {
+        let file_manager = Arc::new(FileManager);
+        let output_handler = Arc::new(OutputHandler::new(Arc::clone(&file_manager)));
+        let parameters = Arc::new(ShellParameters::new("shell", vec!["outer".to_string()]));
+        let executor = Executor::new(
+            file_manager,
+            output_handler,
+            Arc::new(ShellOptions::default()),
+            Arc::clone(&parameters),
+        );
+        (executor, parameters)
+    }
+
+    fn script(content: &str) -> tempfile::NamedTempFile {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), content).unwrap();
+        file
+    }
+
+    #[test]
+    fn and_or_lists_follow_exit_status() {
+        let (executor, _) = executor();
+        assert_eq!(
+            executor.execute_script("nosuchcmd123 || pwd && nosuchcmd123"),
+            Ok(127)
+        );
+        assert_eq!(executor.execute_script("pwd || nosuchcmd123"), Ok(0));
+    }
+
+    #[test]
+    fn exit_stops_execution() {
+        let (executor, _) = executor();
+        assert_eq!(executor.execute("exit 4; pwd"), Ok(ExecutionFlow::Exit(4)));
+    }
+
+    #[test]
+    fn source_return_sets_status_and_stops_script() {
+        let (executor, _) = executor();
+        let file = script("return 3\nexit 9\n");
+        let command = format!("source {}", file.path().display());
+
+        assert_eq!(executor.execute(&command), Ok(ExecutionFlow::Continue));
+        assert_eq!(executor.last_status(), 3);
+    }
+
+    #[test]
+    fn source_restores_positional_parameters() {
+        let (executor, parameters) = executor();
+        let file = script("pwd\n");
+        let command = format!(". {} one two", file.path().display());
+
+        assert_eq!(executor.execute(&command), Ok(ExecutionFlow::Continue));
+        assert_eq!(parameters.positional(), vec!["outer".to_string()]);
+    }
+
+    #[test]
+    fn alias_expands_to_command_list() {
+        let (executor, _) = executor();
+        assert_eq!(
+            executor.execute("alias fail='nosuchcmd123 || return 5'"),
+            Ok(ExecutionFlow::Continue)
+        );
+        assert_eq!(executor.execute_script("fail && pwd"), Ok(1));
+
+        executor.execute("alias ok='pwd; pwd'").unwrap();
+        assert_eq!(executor.execute_script("ok && nosuchcmd123"), Ok(127));
+    }
+
+    #[test]
+    fn return_outside_sourced_script_is_an_error() {
+        let (executor, _) = executor();
+        assert_eq!(executor.execute("return 2"), Ok(ExecutionFlow::Continue));
+        assert_eq!(executor.last_status(), 1);
+    }
+}
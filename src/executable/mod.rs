@@ -1 +1,3 @@
+pub mod cli;
+pub mod executor;
 pub mod repl;
@@ -1,106 +1,244 @@
-use std::char;
-use std::io::{self, stdout, Read, Stdin, Stdout, StdoutLock, Write};
+use std::io::{self, Write};
 use std::sync::Arc;
 
 use anyhow::Result;
 
-use crate::shell::completion;
-use crate::shell::completion::builtins::BuiltinsCompletion;
-use crate::shell::input::input_handler::{InputHandler, InputResult};
-use crate::shell::raw_mode::RawMode;
 use crate::{
-    commands::{
-        builtins::{cd::Cd, echo::Echo, exit::Exit, pwd::Pwd, r#type::Type},
-        registry::CommandRegistry,
-        CommandToken,
-    },
     exceptions::commands::ShellError,
-    external::ExternalCommand,
-    port::{command::CommandResult, shell_component::ShellComponent},
+    executable::executor::{ExecutionFlow, Executor},
+    port::command::CommandResult,
     shell::{
-        file::FileManager, input::input_parser::InputParser, output_handler::OutputHandler,
-        path::PathDirsProvider,
+        completion::builtins::BuiltinsCompletion,
+        history::History,
+        input::{
+            command_list::CommandList,
+            history_expansion::HistoryExpansion,
+            input_handler::{InputHandler, InputResult},
+            line_buffer::LineBuffer,
+            render::Repaint,
+        },
+        options::{ShellOption, ShellOptions},
+        output_handler::OutputHandler,
+        prompt::{self, PromptState},
+        terminal,
     },
 };
 
+const PROMPT: &str = "$ ";
+const CONTINUATION_PROMPT: &str = "> ";
+const DEFAULT_IGNORE_EOF: usize = 10;
+
 pub struct Repl {
-    builtins: CommandRegistry,
-    input_parser: InputParser,
+    executor: Executor,
     output_handler: Arc<OutputHandler>,
     input_handler: InputHandler,
-    // file_manager: Arc<FileManager>,
+    history: Arc<History>,
+    expansion: HistoryExpansion,
+    options: Arc<ShellOptions>,
+    startup_exit: Option<i32>,
 }
 
 impl Repl {
-    pub fn new(file_manager: Arc<FileManager>, output_handler: Arc<OutputHandler>) -> Self {
-        let path_dirs = Arc::new(PathDirsProvider::from_env());
-        let external_command = Arc::new(ExternalCommand::new(Arc::clone(&path_dirs)));
-
-        let mut registry = CommandRegistry::new(Arc::clone(&path_dirs), external_command);
-        registry.register(CommandToken::Exit, Arc::new(Exit));
-        registry.register(CommandToken::Echo, Arc::new(Echo));
-        registry.register(
-            CommandToken::Type,
-            Arc::new(Type::new(Arc::clone(&path_dirs))),
-        );
-        registry.register(CommandToken::Pwd, Arc::new(Pwd));
-        registry.register(
-            CommandToken::Cd,
-            Arc::new(Cd::new(Arc::clone(&file_manager))),
-        );
-
-        let completions = BuiltinsCompletion::new(Arc::clone(&path_dirs));
-
-        Self {
-            builtins: registry,
-            input_parser: InputParser::new(Arc::clone(&file_manager)),
-            input_handler: InputHandler::new(completions), // file_manager,
-            output_handler,
+    pub(crate) fn new(executor: Executor) -> Result<Self, ShellError> {
+        let completions = BuiltinsCompletion::new(executor.path_dirs())
+            .with_aliases(executor.aliases())
+            .with_specs(executor.completion_specs());
+
+        let history = executor.history();
+        if executor.is_interactive() {
+            if let Err(err) = history.load() {
+                executor
+                    .output_handler()
+                    .handle(CommandResult::Error(err.into()), None)?;
+            }
+        }
+
+        terminal::watch_resize();
+        terminal::size();
+
+        let startup_exit = match executor.load_startup_files()? {
+            ExecutionFlow::Exit(code) => Some(code),
+            ExecutionFlow::Continue | ExecutionFlow::Return(_) => None,
+        };
+
+        Ok(Self {
+            output_handler: executor.output_handler(),
+            input_handler: InputHandler::new(completions, Arc::clone(&history))
+                .with_options(executor.options())
+                .with_output_handler(executor.output_handler()),
+            expansion: HistoryExpansion::new(Arc::clone(&history)),
+            history,
+            options: executor.options(),
+            executor,
+            startup_exit,
+        })
+    }
+
+    fn prompt(&self, prompt: &str, content: Option<String>) -> Result<(), ShellError> {
+        let buffer = LineBuffer::new(content.unwrap_or_default());
+        let prompt = self.input_handler.prompt(prompt);
+        self.input_handler
+            .renderer()
+            .render(&mut io::stdout(), &prompt, &buffer, Repaint::Fresh)
+    }
+
+    /// Expanded lines are echoed before they run, lines that fail to
+    /// expand are reported and dropped.
+    fn expand_history(&self, line: String) -> Result<Option<String>, ShellError> {
+        if !self.options.is_enabled(ShellOption::HistExpand) {
+            return Ok(Some(line));
+        }
+
+        match self.expansion.expand(&line) {
+            Ok(Some(expanded)) => {
+                self.output_handler.write_stdout(&format!("{expanded}\n"));
+                Ok(Some(expanded))
+            }
+            Ok(None) => Ok(Some(line)),
+            Err(err) => {
+                self.output_handler
+                    .handle(CommandResult::Error(err.into()), None)?;
+                Ok(None)
+            }
         }
     }
 
-    fn prompt(&self, content: Option<String>) -> Result<(), ShellError> {
-        let prompt = match content {
-            Some(content) => &format!("$ {content}"),
-            None => "$ ",
+    /// Prompt of a new command, taken from `PS1`.
+    fn primary_prompt(&self) -> String {
+        // No variable table yet, so PS1 and PS2 only come from the environment
+        match std::env::var("PS1") {
+            Ok(prompt) => self.expand_prompt(&prompt),
+            Err(_) => PROMPT.to_owned(),
+        }
+    }
+
+    /// Prompt shown while a command spans several lines, taken from `PS2`.
+    fn continuation_prompt(&self) -> String {
+        match std::env::var("PS2") {
+            Ok(prompt) => self.expand_prompt(&prompt),
+            Err(_) => CONTINUATION_PROMPT.to_owned(),
+        }
+    }
+
+    fn expand_prompt(&self, prompt: &str) -> String {
+        let state = PromptState {
+            history_number: self.history.len() + 1,
+            last_status: self.executor.last_status(),
+        };
+        prompt::expand(prompt, &state, |command| {
+            self.executor.capture(command).unwrap_or_default()
+        })
+    }
+
+    /// Joins `line` to the lines read so far, returning the whole entry once
+    /// it forms a complete command.
+    fn accumulate(pending: &mut Option<String>, line: String) -> Option<String> {
+        let entry = match pending.take() {
+            Some(previous) => format!("{previous}\n{line}"),
+            None => line,
         };
-        print!("{prompt}");
 
-        io::stdout()
-            .flush()
-            .map_err(|err| ShellError::Uncontroled(err.to_string()))
+        match CommandList::parse(&entry) {
+            Err(ShellError::IncompleteInput) => {
+                *pending = Some(entry);
+                None
+            }
+            _ => Some(entry),
+        }
     }
 
-    pub fn spawn(&self) -> Result<(), ShellError> {
-        let mut previous_content: Option<String> = None;
+    /// With `ignoreeof` set, `IGNOREEOF` consecutive end-of-file reads are
+    /// ignored before the shell leaves.
+    fn ignore_eof(&self, eof_count: usize) -> bool {
+        if !self.options.is_enabled(ShellOption::IgnoreEof) {
+            return false;
+        }
+
+        let limit = std::env::var("IGNOREEOF")
+            .ok()
+            .and_then(|count| count.parse().ok())
+            .unwrap_or(DEFAULT_IGNORE_EOF);
+        if eof_count > limit {
+            return false;
+        }
+
+        self.output_handler
+            .write_stdout("\r\nUse \"exit\" to leave the shell.\n");
+        true
+    }
+
+    /// History is only persisted by interactive shells.
+    fn save_history(&self) -> Result<(), ShellError> {
+        if !self.executor.is_interactive() {
+            return Ok(());
+        }
+
+        match self.history.save() {
+            Ok(()) => Ok(()),
+            Err(err) => self
+                .output_handler
+                .handle(CommandResult::Error(err.into()), None),
+        }
+    }
+
+    pub fn spawn(&self) -> Result<i32, ShellError> {
+        if let Some(code) = self.startup_exit {
+            return Ok(code);
+        }
+
+        let mut pending: Option<String> = None;
+        let mut eof_count = 0;
         loop {
-            self.prompt(previous_content.clone())?;
+            let prompt = match pending {
+                Some(_) => self.continuation_prompt(),
+                None => self.primary_prompt(),
+            };
+            self.prompt(&prompt, None)?;
 
-            let input = self.input_handler.handle(previous_content.clone())?;
+            let input = self.input_handler.handle(&prompt, None)?;
 
-            if previous_content.is_some() {
-                previous_content = None
+            if !matches!(input, InputResult::Eof) {
+                eof_count = 0;
             }
 
             match input {
-                InputResult::Reset => self.output_handler.write_stdout("^C\r\n"),
-                InputResult::MultiCompletion {
-                    completion_items,
-                    input,
-                } => {
-                    self.output_handler.write_stdout(&completion_items);
-                    previous_content = Some(input);
-                    // self.prompt(Some(&input))?;
+                InputResult::Reset => {
+                    pending = None;
+                    self.output_handler.write_stdout("^C\r\n");
+                }
+                // An unfinished entry is reported rather than leaving the shell
+                InputResult::Eof if pending.is_some() => {
+                    self.output_handler.write_stdout("\r\n");
+                    if let Some(entry) = pending.take() {
+                        self.executor.execute(&entry)?;
+                    }
+                }
+                InputResult::Eof => {
+                    eof_count += 1;
+                    if !self.ignore_eof(eof_count) {
+                        if self.executor.is_interactive() {
+                            self.output_handler.write_stdout("exit\r\n");
+                        }
+                        self.save_history()?;
+                        return Ok(self.executor.last_status());
+                    }
                 }
                 InputResult::Input(buffer) => {
-                    let (parsed_command, redirection) = self.input_parser.parse(buffer.trim())?;
-                    let command = self.builtins.execute(parsed_command);
-                    match command {
-                        Err(err) => self
-                            .output_handler
-                            .handle(CommandResult::Error(err), redirection),
-                        Ok(res) => self.output_handler.handle(res, redirection),
-                    }?;
+                    match self.expand_history(buffer)? {
+                        Some(line) => {
+                            if let Some(entry) = Self::accumulate(&mut pending, line) {
+                                self.history.add(&entry);
+                                if let ExecutionFlow::Exit(code) =
+                                    self.executor.execute(entry.trim())?
+                                {
+                                    self.save_history()?;
+                                    return Ok(code);
+                                }
+                            }
+                        }
+                        // A line that fails to expand drops the whole entry
+                        None => pending = None,
+                    }
                 }
             }
 
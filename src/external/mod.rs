@@ -1,4 +1,8 @@
-use std::sync::Arc;
+use std::{
+    os::unix::process::{CommandExt, ExitStatusExt},
+    process::ExitStatus,
+    sync::Arc,
+};
 
 use crate::{
     exceptions::commands::ShellError,
@@ -8,32 +12,54 @@ use crate::{
 
 pub struct ExternalCommand {
     path_dirs: Arc<PathDirsProvider>,
+    /// Variables set for the command only.
+    envs: Vec<(String, String)>,
 }
 
 impl ExternalCommand {
     pub fn new(path_dirs: Arc<PathDirsProvider>) -> Self {
-        Self { path_dirs }
+        Self {
+            path_dirs,
+            envs: Vec::new(),
+        }
+    }
+
+    pub fn with_env(mut self, name: &str, value: &str) -> Self {
+        self.envs.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    fn exit_status(&self, status: ExitStatus) -> i32 {
+        status
+            .code()
+            .or_else(|| status.signal().map(|signal| 128 + signal))
+            .unwrap_or(1)
     }
 }
 
 impl ShellComponent for ExternalCommand {
     fn handler(&self, command: &str, args: &[String]) -> Result<CommandResult, ShellError> {
-        if self.path_dirs.find_executable(command).is_some() {
-            let output = std::process::Command::new(command)
+        if let Some(exe_path) = self.path_dirs.lookup(command) {
+            let output = std::process::Command::new(exe_path)
+                .arg0(command)
                 .args(args)
+                .envs(self.envs.iter().map(|(name, value)| (name, value)))
                 // .stdout(Stdio::inherit())
                 // .stderr(Stdio::inherit())
                 // .status()
                 .output()
                 .map_err(|err| ShellError::ExternalError(err.to_string()))?;
 
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
 
-            return Ok(CommandResult::Stdio(stdout.to_string(), stderr.to_string()));
+            return match self.exit_status(output.status) {
+                0 => Ok(CommandResult::Stdio(stdout, stderr)),
+                code => Ok(CommandResult::Failure(stdout, stderr, code)),
+            };
         }
 
-        Err(ShellError::CommandNotFound(command.to_owned()).into())
+        Err(ShellError::CommandNotFound(command.to_owned()))
     }
 
     fn next(&self) -> Option<Arc<dyn ShellComponent>> {
@@ -1,9 +1,18 @@
-use std::sync::Arc;
+use std::{io::IsTerminal, sync::Arc};
 
 use crate::{
-    executable::repl::Repl,
+    executable::{
+        cli::{CliAction, CliArgs, ShellMode},
+        executor::Executor,
+        repl::Repl,
+    },
     port::command::CommandResult,
-    shell::{file::FileManager, output_handler::OutputHandler},
+    shell::{
+        file::FileManager,
+        options::{ShellOption, ShellOptions},
+        output_handler::OutputHandler,
+        parameters::ShellParameters,
+    },
 };
 
 pub(crate) mod commands;
@@ -15,11 +24,53 @@ pub mod shell;
 
 fn main() -> Result<(), exceptions::commands::ShellError> {
     let file_manager = FileManager.into();
-    let output_handler = OutputHandler::new(Arc::clone(&file_manager)).into();
+    let output_handler: Arc<OutputHandler> = OutputHandler::new(Arc::clone(&file_manager)).into();
 
-    if let Err(err) = Repl::new(file_manager, Arc::clone(&output_handler)).spawn() {
-        let error = CommandResult::Error(err);
-        return output_handler.handle(error, None);
+    let cli = match CliArgs::parse(std::env::args()) {
+        Ok(cli) => cli,
+        Err(err) => {
+            output_handler.handle(CommandResult::Error(err), None)?;
+            eprint!("{}", CliArgs::usage());
+            std::process::exit(2);
+        }
+    };
+
+    match cli.action() {
+        CliAction::Help => {
+            print!("{}", CliArgs::usage());
+            return Ok(());
+        }
+        CliAction::Version => {
+            print!("{}", CliArgs::version());
+            return Ok(());
+        }
+        CliAction::Run => {}
+    }
+
+    let options = Arc::new(ShellOptions::new(cli.options().iter().copied()));
+    if *cli.mode() == ShellMode::Stdin {
+        options.enable(ShellOption::HistExpand);
+        options.enable(ShellOption::Emacs);
+        if std::io::stdin().is_terminal() {
+            options.enable(ShellOption::Interactive);
+        }
+    }
+    let parameters = Arc::new(ShellParameters::new(cli.name(), cli.positional().to_vec()));
+    let executor = Executor::new(
+        file_manager,
+        Arc::clone(&output_handler),
+        options,
+        parameters,
+    );
+
+    let status = match cli.mode() {
+        ShellMode::Command(command) => executor.execute_script(command),
+        ShellMode::Script(path) => executor.execute_file(path),
+        ShellMode::Stdin => Repl::new(executor).and_then(|repl| repl.spawn()),
+    };
+
+    match status {
+        Ok(code) => std::process::exit(code),
+        Err(err) => output_handler.handle(CommandResult::Error(err), None),
     }
-    Ok(())
 }
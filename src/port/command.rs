@@ -1,9 +1,14 @@
+use std::path::PathBuf;
+
 use crate::exceptions::commands::ShellError;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum CommandResult {
     Exit(i32),
     Stdio(String, String),
+    Failure(String, String, i32),
+    Source(PathBuf, Vec<String>),
+    Return(i32),
     Empty,
     Error(ShellError),
 }
@@ -16,6 +21,14 @@ impl CommandResult {
     pub fn stderr(buffer: impl Into<String>) -> Self {
         Self::Stdio(String::new(), buffer.into())
     }
+
+    pub fn exit_status(&self) -> i32 {
+        match self {
+            Self::Exit(code) | Self::Return(code) | Self::Failure(_, _, code) => *code,
+            Self::Error(error) => error.exit_status(),
+            Self::Stdio(_, _) | Self::Source(_, _) | Self::Empty => 0,
+        }
+    }
 }
 
 pub(crate) trait Command {
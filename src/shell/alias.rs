@@ -0,0 +1,106 @@
+use std::{collections::BTreeMap, sync::RwLock};
+
+#[derive(Debug, Default)]
+pub struct AliasTable {
+    aliases: RwLock<BTreeMap<String, String>>,
+}
+
+impl AliasTable {
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.aliases
+            .read()
+            .ok()
+            .and_then(|aliases| aliases.get(name).cloned())
+    }
+
+    pub fn set(&self, name: &str, value: &str) {
+        if let Ok(mut aliases) = self.aliases.write() {
+            aliases.insert(name.to_owned(), value.to_owned());
+        }
+    }
+
+    pub fn remove(&self, name: &str) -> Option<String> {
+        self.aliases
+            .write()
+            .ok()
+            .and_then(|mut aliases| aliases.remove(name))
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut aliases) = self.aliases.write() {
+            aliases.clear();
+        }
+    }
+
+    /// All aliases sorted by name.
+    pub fn list(&self) -> Vec<(String, String)> {
+        self.aliases
+            .read()
+            .map(|aliases| {
+                aliases
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.aliases
+            .read()
+            .map(|aliases| aliases.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// The `alias` command that defines `name`, as the shell reads it back.
+    pub fn definition(name: &str, value: &str) -> String {
+        format!("alias {name}='{}'\n", value.replace('\'', "'\\''"))
+    }
+
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty()
+            && !name.chars().any(|char| {
+                char.is_whitespace() || matches!(char, '/' | '$' | '`' | '=' | '\'' | '"' | '\\')
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_get_remove() {
+        let aliases = AliasTable::default();
+        aliases.set("ll", "ls -la");
+        assert_eq!(aliases.get("ll"), Some("ls -la".to_owned()));
+        assert_eq!(aliases.remove("ll"), Some("ls -la".to_owned()));
+        assert_eq!(aliases.get("ll"), None);
+    }
+
+    #[test]
+    fn list_is_sorted() {
+        let aliases = AliasTable::default();
+        aliases.set("b", "two");
+        aliases.set("a", "one");
+        assert_eq!(
+            aliases.list(),
+            vec![
+                ("a".to_owned(), "one".to_owned()),
+                ("b".to_owned(), "two".to_owned())
+            ]
+        );
+        aliases.clear();
+        assert!(aliases.names().is_empty());
+    }
+
+    #[test]
+    fn validates_names() {
+        assert!(AliasTable::is_valid_name("ll"));
+        assert!(AliasTable::is_valid_name("git-st"));
+        assert!(!AliasTable::is_valid_name(""));
+        assert!(!AliasTable::is_valid_name("a/b"));
+        assert!(!AliasTable::is_valid_name("a b"));
+        assert!(!AliasTable::is_valid_name("$x"));
+    }
+}
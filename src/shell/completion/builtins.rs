@@ -1,12 +1,13 @@
-use std::{collections::HashSet, sync::Arc};
+use std::sync::Arc;
 
 use crate::{
     commands::CommandToken,
     shell::{
+        alias::AliasTable,
         completion::{
-            self,
-            path_dirs::{self, PathDirsCompletion},
-            Completion, CompletionComponent,
+            context::CompletionContext, matching::Matching, path_dirs::PathDirsCompletion,
+            programmable::ProgrammableCompletion, spec::CompletionSpecs, Completed, Completion,
+            CompletionComponent,
         },
         path::PathDirsProvider,
     },
@@ -14,24 +15,51 @@ use crate::{
 
 pub struct BuiltinsCompletion {
     builtins: Vec<String>,
+    aliases: Arc<AliasTable>,
+    path_dirs: Arc<PathDirsProvider>,
     next: Arc<dyn CompletionComponent>,
 }
 
 impl BuiltinsCompletion {
     pub fn new(path_dirs: Arc<PathDirsProvider>) -> Self {
         let builtins = CommandToken::into_completion();
-        let next = Arc::new(PathDirsCompletion::new(path_dirs));
-        Self { builtins, next }
+        let next = Arc::new(PathDirsCompletion::new(Arc::clone(&path_dirs)));
+        Self {
+            builtins,
+            aliases: Arc::new(AliasTable::default()),
+            path_dirs,
+            next,
+        }
+    }
+
+    pub fn with_aliases(mut self, aliases: Arc<AliasTable>) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    /// Arguments of commands with a spec are completed from it before the
+    /// default completion.
+    pub fn with_specs(mut self, specs: Arc<CompletionSpecs>) -> Self {
+        self.next = Arc::new(ProgrammableCompletion::new(
+            specs,
+            Arc::clone(&self.path_dirs),
+        ));
+        self
     }
 }
 
 impl Completion for BuiltinsCompletion {
-    fn completion_items(&self, args: &str) -> Vec<String> {
-        self.builtins
+    fn completion_items(&self, args: &str, matching: Matching) -> Vec<String> {
+        let mut items: Vec<String> = self
+            .builtins
             .iter()
             .cloned()
-            .filter(|builtin| builtin.starts_with(args))
-            .collect()
+            .chain(self.aliases.names())
+            .filter(|item| matching.matches(args, item))
+            .collect();
+        items.sort();
+        items.dedup();
+        items
     }
 }
 
@@ -40,8 +68,11 @@ impl CompletionComponent for BuiltinsCompletion {
         Some(Arc::clone(&self.next))
     }
 
-    fn handler(&self, args: &str, multiple: bool) -> Option<String> {
-        self.complete(args, multiple)
+    fn handler(&self, context: &CompletionContext, multiple: bool) -> Option<Completed> {
+        if !context.expects_command() {
+            return None;
+        }
+        self.complete(context.word(), context.matching(), multiple)
     }
 }
 
@@ -56,10 +87,22 @@ mod tests {
     fn complete_partial_command_with_single_match() {
         let completion = setup();
 
-        assert_eq!(completion.complete("ec", false), Some("ho ".to_string()));
-        assert_eq!(completion.complete("typ", false), Some("e ".to_string()));
-        assert_eq!(completion.complete("pw", false), Some("d ".to_string()));
-        assert_eq!(completion.complete("exi", false), Some("t ".to_string()));
+        assert_eq!(
+            completion.complete("ec", Matching::Prefix, false),
+            Some(Completed::Insert("ho ".to_string()))
+        );
+        assert_eq!(
+            completion.complete("typ", Matching::Prefix, false),
+            Some(Completed::Insert("e ".to_string()))
+        );
+        assert_eq!(
+            completion.complete("pw", Matching::Prefix, false),
+            Some(Completed::Insert("d ".to_string()))
+        );
+        assert_eq!(
+            completion.complete("exi", Matching::Prefix, false),
+            Some(Completed::Insert("t ".to_string()))
+        );
     }
 
     #[test]
@@ -67,25 +110,37 @@ mod tests {
         let completion = setup();
 
         // "e" matches both "echo" and "exit"
-        assert_eq!(completion.complete("e", false), None);
+        assert_eq!(completion.complete("e", Matching::Prefix, false), None);
     }
 
     #[test]
     fn complete_full_command_returns_empty_string() {
         let completion = setup();
 
-        assert_eq!(completion.complete("echo", false), Some(" ".to_string()));
-        assert_eq!(completion.complete("exit", false), Some(" ".to_string()));
-        assert_eq!(completion.complete("pwd", false), Some(" ".to_string()));
+        assert_eq!(
+            completion.complete("echo", Matching::Prefix, false),
+            Some(Completed::Insert(" ".to_string()))
+        );
+        assert_eq!(
+            completion.complete("exit", Matching::Prefix, false),
+            Some(Completed::Insert(" ".to_string()))
+        );
+        assert_eq!(
+            completion.complete("pwd", Matching::Prefix, false),
+            Some(Completed::Insert(" ".to_string()))
+        );
     }
 
     #[test]
     fn complete_no_match_returns_none() {
         let completion = setup();
 
-        assert_eq!(completion.complete("xyz", false), None);
-        assert_eq!(completion.complete("ls", false), None);
-        assert_eq!(completion.complete("unknown", false), None);
+        assert_eq!(completion.complete("xyz", Matching::Prefix, false), None);
+        assert_eq!(completion.complete("ls", Matching::Prefix, false), None);
+        assert_eq!(
+            completion.complete("unknown", Matching::Prefix, false),
+            None
+        );
     }
 
     #[test]
@@ -93,6 +148,18 @@ mod tests {
         let completion = setup();
 
         // Empty string matches all commands - ambiguous
-        assert_eq!(completion.complete("", false), None);
+        assert_eq!(completion.complete("", Matching::Prefix, false), None);
+    }
+
+    #[test]
+    fn complete_alias_names() {
+        let aliases = Arc::new(AliasTable::default());
+        aliases.set("gitlog", "git log --oneline");
+        let completion = setup().with_aliases(aliases);
+
+        assert_eq!(
+            completion.complete("gitl", Matching::Prefix, false),
+            Some(Completed::Insert("og ".to_string()))
+        );
     }
 }
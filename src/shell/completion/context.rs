@@ -0,0 +1,421 @@
+use crate::shell::{
+    completion::matching::Matching,
+    input::commons::{BACK_SLASH, DOUBLE_QUOTE, SINGLE_QUOTE},
+};
+
+/// Commands whose arguments are command names.
+const COMMAND_ARGUMENTS: [&str; 3] = ["type", "command", "which"];
+/// Commands whose arguments are variable names.
+const VARIABLE_ARGUMENTS: [&str; 2] = ["export", "unset"];
+/// Characters escaped with a backslash when completed outside quotes.
+const SPECIAL_CHARS: &str = " \t\n\"'\\$`;|&<>()!*?[]{}";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordPosition {
+    Command,
+    /// 1-based index of the argument.
+    Argument(usize),
+    /// Target of `>`, `>>`, `2>` or `<`.
+    Redirection,
+    /// A word starting with `$`.
+    Variable,
+}
+
+/// The quote left open before the cursor. Completed text is escaped so
+/// that it reads back as typed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Quoting {
+    #[default]
+    Unquoted,
+    Single,
+    Double,
+}
+
+impl Quoting {
+    /// Escapes `text`, `word_start` tells whether it begins the word, where
+    /// `~` and `#` have a meaning of their own. A `~/` there is kept as the
+    /// home directory.
+    pub fn escape(self, text: &str, word_start: bool) -> String {
+        let mut escaped = String::new();
+        for (idx, char) in text.chars().enumerate() {
+            match self {
+                Quoting::Unquoted
+                    if SPECIAL_CHARS.contains(char)
+                        || (word_start
+                            && idx == 0
+                            && (char == '#' || (char == '~' && !text.starts_with("~/")))) =>
+                {
+                    escaped.push(BACK_SLASH);
+                    escaped.push(char);
+                }
+                // Single quotes can't be escaped, the quote is closed around it
+                Quoting::Single if char == SINGLE_QUOTE => escaped.push_str("'\\''"),
+                Quoting::Double if matches!(char, DOUBLE_QUOTE | BACK_SLASH) => {
+                    escaped.push(BACK_SLASH);
+                    escaped.push(char);
+                }
+                _ => escaped.push(char),
+            }
+        }
+        escaped
+    }
+
+    /// The quote, reopened when the word is replaced and closed once it is
+    /// completed in full.
+    pub fn quote(self) -> &'static str {
+        match self {
+            Quoting::Unquoted => "",
+            Quoting::Single => "'",
+            Quoting::Double => "\"",
+        }
+    }
+}
+
+/// Where the word under the cursor sits in the command being typed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletionContext {
+    line: String,
+    command: Option<String>,
+    previous: Option<String>,
+    word: String,
+    typed: String,
+    quoting: Quoting,
+    matching: Matching,
+    position: WordPosition,
+}
+
+#[derive(Debug, Default)]
+struct ContextState {
+    words: Vec<String>,
+    /// The word as typed, and with its quotes and escapes removed.
+    current: String,
+    unquoted: String,
+    quote: Option<char>,
+    escape_next: bool,
+    redirection: bool,
+}
+
+impl ContextState {
+    fn finish_word(&mut self) {
+        if self.current.is_empty() {
+            return;
+        }
+        self.current.clear();
+        let word = std::mem::take(&mut self.unquoted);
+        if self.redirection {
+            self.redirection = false;
+        } else {
+            self.words.push(word);
+        }
+    }
+
+    fn start_redirection(&mut self) {
+        // A file descriptor such as the `2` of `2>` isn't an argument
+        if !self.current.is_empty() && self.current.chars().all(|char| char.is_ascii_digit()) {
+            self.current.clear();
+            self.unquoted.clear();
+        }
+        self.finish_word();
+        self.redirection = true;
+    }
+
+    fn start_command(&mut self) {
+        self.finish_word();
+        self.words.clear();
+        self.redirection = false;
+    }
+}
+
+impl CompletionContext {
+    /// Parses the text before the cursor. Only the last command of a list
+    /// or pipeline is considered.
+    pub fn parse(line: &str) -> Self {
+        let mut state = ContextState::default();
+        let mut chars = line.chars().peekable();
+
+        while let Some(char) = chars.next() {
+            if state.escape_next {
+                state.escape_next = false;
+                state.current.push(char);
+                // Inside double quotes only `"` and `\` are escaped
+                if state.quote == Some(DOUBLE_QUOTE) && !matches!(char, DOUBLE_QUOTE | BACK_SLASH) {
+                    state.unquoted.push(BACK_SLASH);
+                }
+                state.unquoted.push(char);
+                continue;
+            }
+
+            match (char, state.quote) {
+                (BACK_SLASH, quote) if quote != Some(SINGLE_QUOTE) => {
+                    state.escape_next = true;
+                    state.current.push(char);
+                }
+                (SINGLE_QUOTE | DOUBLE_QUOTE, None) => {
+                    state.quote = Some(char);
+                    state.current.push(char);
+                }
+                (char, Some(quote)) => {
+                    if char == quote {
+                        state.quote = None;
+                    } else {
+                        state.unquoted.push(char);
+                    }
+                    state.current.push(char);
+                }
+                ('>' | '<', None) => {
+                    if !(chars.peek() == Some(&'>') && char == '>') {
+                        state.start_redirection();
+                    }
+                }
+                ('&', None) if chars.peek() == Some(&'>') => {}
+                (';' | '|' | '&', None) => state.start_command(),
+                (char, None) if char.is_whitespace() => state.finish_word(),
+                (char, None) => {
+                    state.current.push(char);
+                    state.unquoted.push(char);
+                }
+            }
+        }
+
+        let position = if state.redirection {
+            WordPosition::Redirection
+        } else if state.current.starts_with('$') || state.current.starts_with("\"$") {
+            WordPosition::Variable
+        } else if state.words.is_empty() {
+            WordPosition::Command
+        } else {
+            WordPosition::Argument(state.words.len())
+        };
+
+        let quoting = match state.quote {
+            Some(SINGLE_QUOTE) => Quoting::Single,
+            Some(_) => Quoting::Double,
+            None => Quoting::Unquoted,
+        };
+
+        Self {
+            line: line.to_owned(),
+            command: state.words.first().cloned(),
+            previous: state.words.last().cloned(),
+            word: state.unquoted,
+            typed: state.current,
+            quoting,
+            matching: Matching::default(),
+            position,
+        }
+    }
+
+    /// The first argument of `command`, as `compgen` completes it.
+    pub fn argument(command: &str, word: &str) -> Self {
+        Self {
+            line: format!("{command} {word}"),
+            command: Some(command.to_owned()),
+            previous: Some(command.to_owned()),
+            word: word.to_owned(),
+            typed: word.to_owned(),
+            quoting: Quoting::Unquoted,
+            matching: Matching::default(),
+            position: WordPosition::Argument(1),
+        }
+    }
+
+    pub fn with_matching(mut self, matching: Matching) -> Self {
+        self.matching = matching;
+        self
+    }
+
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+
+    pub fn command(&self) -> Option<&str> {
+        self.command.as_deref()
+    }
+
+    pub fn word(&self) -> &str {
+        &self.word
+    }
+
+    /// The word as typed, with its quotes and escapes.
+    pub fn typed(&self) -> &str {
+        &self.typed
+    }
+
+    pub fn quoting(&self) -> Quoting {
+        self.quoting
+    }
+
+    pub fn matching(&self) -> Matching {
+        self.matching
+    }
+
+    /// The word before the one being completed, in the same command.
+    pub fn previous_word(&self) -> Option<&str> {
+        self.previous.as_deref()
+    }
+
+    pub fn position(&self) -> WordPosition {
+        self.position
+    }
+
+    pub fn expects_command(&self) -> bool {
+        match self.position {
+            WordPosition::Command => true,
+            WordPosition::Argument(_) => self.command_in(&COMMAND_ARGUMENTS),
+            _ => false,
+        }
+    }
+
+    pub fn expects_variable(&self) -> bool {
+        match self.position {
+            WordPosition::Variable => true,
+            WordPosition::Argument(_) => self.command_in(&VARIABLE_ARGUMENTS),
+            _ => false,
+        }
+    }
+
+    pub fn expects_file(&self) -> bool {
+        match self.position {
+            WordPosition::Redirection => true,
+            WordPosition::Argument(_) => !self.expects_command() && !self.expects_variable(),
+            _ => false,
+        }
+    }
+
+    /// `cd` only takes directories.
+    pub fn expects_directory(&self) -> bool {
+        matches!(self.position, WordPosition::Argument(_)) && self.command_in(&["cd"])
+    }
+
+    fn command_in(&self, commands: &[&str]) -> bool {
+        self.command()
+            .is_some_and(|command| commands.contains(&command))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(line: &str) -> (Option<String>, String, WordPosition) {
+        let context = CompletionContext::parse(line);
+        (
+            context.command().map(str::to_owned),
+            context.word().to_owned(),
+            context.position(),
+        )
+    }
+
+    #[test]
+    fn command_word() {
+        assert_eq!(
+            position("ec"),
+            (None, "ec".to_owned(), WordPosition::Command)
+        );
+        assert_eq!(position(""), (None, String::new(), WordPosition::Command));
+        assert_eq!(
+            position("echo a; gi"),
+            (None, "gi".to_owned(), WordPosition::Command)
+        );
+        assert_eq!(
+            position("ls | gr"),
+            (None, "gr".to_owned(), WordPosition::Command)
+        );
+    }
+
+    #[test]
+    fn argument_index() {
+        assert_eq!(
+            position("cat src/ma"),
+            (
+                Some("cat".to_owned()),
+                "src/ma".to_owned(),
+                WordPosition::Argument(1)
+            )
+        );
+        assert_eq!(
+            position("cp a "),
+            (
+                Some("cp".to_owned()),
+                String::new(),
+                WordPosition::Argument(2)
+            )
+        );
+        assert_eq!(
+            position("echo 'a b' c"),
+            (
+                Some("echo".to_owned()),
+                "c".to_owned(),
+                WordPosition::Argument(2)
+            )
+        );
+    }
+
+    #[test]
+    fn redirection_target() {
+        assert_eq!(position("echo a > ou").2, WordPosition::Redirection);
+        assert_eq!(position("echo a 2>ou").1, "ou");
+        assert_eq!(position("echo a >> ").2, WordPosition::Redirection);
+        assert_eq!(position("echo a > out b").2, WordPosition::Argument(2));
+        assert_eq!(position("ls 2> err ").2, WordPosition::Argument(1));
+    }
+
+    #[test]
+    fn variable_word() {
+        assert_eq!(
+            position("echo $HO"),
+            (
+                Some("echo".to_owned()),
+                "$HO".to_owned(),
+                WordPosition::Variable
+            )
+        );
+    }
+
+    #[test]
+    fn quotes_and_escapes_are_removed() {
+        let context = CompletionContext::parse("cat My\\ Doc");
+        assert_eq!(
+            (context.word(), context.quoting()),
+            ("My Doc", Quoting::Unquoted)
+        );
+        let context = CompletionContext::parse("cat 'a b' \"x\\\"y\\z");
+        assert_eq!(context.previous_word(), Some("a b"));
+        assert_eq!(
+            (context.word(), context.quoting()),
+            ("x\"y\\z", Quoting::Double)
+        );
+        let context = CompletionContext::parse("cat 'it");
+        assert_eq!((context.word(), context.quoting()), ("it", Quoting::Single));
+    }
+
+    #[test]
+    fn escape_for_quoting() {
+        assert_eq!(
+            Quoting::Unquoted.escape("My Documents/", false),
+            "My\\ Documents/"
+        );
+        assert_eq!(Quoting::Unquoted.escape("~a~", true), "\\~a~");
+        assert_eq!(Quoting::Unquoted.escape("~/a~", true), "~/a~");
+        assert_eq!(Quoting::Single.escape("a'b", false), "a'\\''b");
+        assert_eq!(Quoting::Double.escape("a\"b$", false), "a\\\"b$");
+    }
+
+    #[test]
+    fn previous_word_and_line() {
+        let context = CompletionContext::parse("ls; git commit -");
+        assert_eq!(context.previous_word(), Some("commit"));
+        assert_eq!(context.line(), "ls; git commit -");
+        assert_eq!(CompletionContext::parse("gi").previous_word(), None);
+    }
+
+    #[test]
+    fn expectations_per_command() {
+        assert!(CompletionContext::parse("type ec").expects_command());
+        assert!(!CompletionContext::parse("type ec").expects_file());
+        assert!(CompletionContext::parse("cd sr").expects_directory());
+        assert!(CompletionContext::parse("unset PA").expects_variable());
+        assert!(CompletionContext::parse("cat > f").expects_file());
+        assert!(!CompletionContext::parse("ca").expects_file());
+    }
+}
@@ -0,0 +1,191 @@
+use std::{path::PathBuf, sync::Arc};
+
+use crate::shell::completion::{
+    context::CompletionContext, matching::Matching, Completed, Completion, CompletionComponent,
+};
+
+/// Completes the current word as a path, relative to the working directory,
+/// absolute or under `~/`. Directories are completed with a trailing `/`.
+#[derive(Default)]
+pub struct FileCompletion {
+    directories_only: bool,
+}
+
+impl FileCompletion {
+    pub fn directories() -> Self {
+        Self {
+            directories_only: true,
+        }
+    }
+
+    /// Splits `word` into the directory to read, as typed, and the prefix
+    /// of the entries to match.
+    fn split(word: &str) -> (&str, &str) {
+        match word.rfind('/') {
+            Some(idx) => (&word[..=idx], &word[idx + 1..]),
+            None => ("", word),
+        }
+    }
+
+    fn resolve(dir: &str) -> Option<PathBuf> {
+        if dir.is_empty() {
+            return Some(PathBuf::from("."));
+        }
+        match dir.strip_prefix("~/") {
+            Some(rest) => std::env::home_dir().map(|home| home.join(rest)),
+            None => Some(PathBuf::from(dir)),
+        }
+    }
+}
+
+impl Completion for FileCompletion {
+    fn completion_items(&self, word: &str, matching: Matching) -> Vec<String> {
+        let (dir, prefix) = Self::split(word);
+        let Some(entries) = Self::resolve(dir).and_then(|path| std::fs::read_dir(path).ok()) else {
+            return Vec::new();
+        };
+
+        entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                // Hidden entries only when the prefix asks for them
+                if !matching.matches(prefix, &name)
+                    || (name.starts_with('.') && !prefix.starts_with('.'))
+                {
+                    return None;
+                }
+                let is_dir = std::fs::metadata(entry.path()).is_ok_and(|meta| meta.is_dir());
+                if self.directories_only && !is_dir {
+                    return None;
+                }
+                Some(format!("{dir}{name}{}", if is_dir { "/" } else { "" }))
+            })
+            .collect()
+    }
+
+    /// Only the names are matched, the directory is the one typed.
+    fn score(&self, word: &str, item: &str, matching: Matching) -> Option<i64> {
+        let (_, name) = Self::split(item.trim_end_matches('/'));
+        matching.score(Self::split(word).1, name)
+    }
+
+    fn terminator(&self, item: &str) -> &'static str {
+        if item.ends_with('/') {
+            ""
+        } else {
+            " "
+        }
+    }
+
+    /// Entries are listed by name, without the directory typed so far.
+    fn label(&self, item: &str) -> String {
+        let trimmed = item.trim_end_matches('/');
+        let (_, name) = Self::split(trimmed);
+        format!("{name}{}", &item[trimmed.len()..])
+    }
+}
+
+impl CompletionComponent for FileCompletion {
+    fn handler(&self, context: &CompletionContext, multiple: bool) -> Option<Completed> {
+        if context.expects_directory() && !self.directories_only {
+            return FileCompletion::directories().handler(context, multiple);
+        }
+        if !context.expects_file() {
+            return None;
+        }
+        self.complete_word(context, multiple)
+    }
+
+    fn next(&self) -> Option<Arc<dyn CompletionComponent>> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "").unwrap();
+        std::fs::write(dir.path().join("src/mod.rs"), "").unwrap();
+        std::fs::write(dir.path().join(".hidden"), "").unwrap();
+        dir
+    }
+
+    #[test]
+    fn complete_file_in_directory() {
+        let dir = setup();
+        let root = dir.path().to_str().unwrap();
+
+        assert_eq!(
+            FileCompletion::default().complete(&format!("{root}/src/ma"), Matching::Prefix, false),
+            Some(Completed::Insert("in.rs ".to_string()))
+        );
+        assert_eq!(
+            FileCompletion::default()
+                .complete(&format!("{root}/src/m"), Matching::Prefix, true)
+                .map(|completed| completed.to_string()),
+            Some("main.rs  mod.rs".to_string())
+        );
+    }
+
+    #[test]
+    fn directories_end_with_slash() {
+        let dir = setup();
+        let root = dir.path().to_str().unwrap();
+
+        assert_eq!(
+            FileCompletion::default().complete(&format!("{root}/sr"), Matching::Prefix, false),
+            Some(Completed::Insert("c/".to_string()))
+        );
+        assert_eq!(
+            FileCompletion::default()
+                .complete(&format!("{root}/s"), Matching::Prefix, true)
+                .map(|completed| completed.to_string()),
+            Some("src/".to_string())
+        );
+    }
+
+    #[test]
+    fn hidden_entries_need_a_dot() {
+        let dir = setup();
+        let root = dir.path().to_str().unwrap();
+
+        assert_eq!(
+            FileCompletion::default()
+                .complete(&format!("{root}/"), Matching::Prefix, true)
+                .map(|completed| completed.to_string()),
+            Some("src/".to_string())
+        );
+        assert_eq!(
+            FileCompletion::default().complete(&format!("{root}/.h"), Matching::Prefix, false),
+            Some(Completed::Insert("idden ".to_string()))
+        );
+    }
+
+    #[test]
+    fn cd_only_offers_directories() {
+        let dir = setup();
+        std::fs::write(dir.path().join("script"), "").unwrap();
+        let root = dir.path().to_str().unwrap();
+
+        let context = CompletionContext::parse(&format!("cd {root}/s"));
+        assert_eq!(
+            FileCompletion::default().handler(&context, false),
+            Some(Completed::Insert("rc/".to_string()))
+        );
+        let context = CompletionContext::parse(&format!("cat {root}/s"));
+        assert_eq!(FileCompletion::default().handler(&context, false), None);
+    }
+
+    #[test]
+    fn missing_directory_has_no_completion() {
+        assert_eq!(
+            FileCompletion::default().complete("/does/not/exist/x", Matching::Prefix, false),
+            None
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use crate::shell::options::{ShellOption, ShellOptions};
+
+const MATCH_SCORE: i64 = 16;
+const CONSECUTIVE_BONUS: i64 = 8;
+const BOUNDARY_BONUS: i64 = 8;
+const GAP_PENALTY: i64 = 3;
+const LEADING_GAP_PENALTY: i64 = 1;
+
+/// How the typed word is matched against completion items, chosen with
+/// `set -o compignorecase`, `compsubstring` or `compfuzzy`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Matching {
+    #[default]
+    Prefix,
+    IgnoreCase,
+    Substring,
+    Fuzzy,
+}
+
+impl Matching {
+    pub fn from_options(options: &ShellOptions) -> Self {
+        if options.is_enabled(ShellOption::CompFuzzy) {
+            Self::Fuzzy
+        } else if options.is_enabled(ShellOption::CompSubstring) {
+            Self::Substring
+        } else if options.is_enabled(ShellOption::CompIgnoreCase) {
+            Self::IgnoreCase
+        } else {
+            Self::Prefix
+        }
+    }
+
+    pub fn matches(self, word: &str, item: &str) -> bool {
+        self.score(word, item).is_some()
+    }
+
+    /// How well `item` matches `word`, higher is better. `None` when it
+    /// doesn't match at all.
+    pub fn score(self, word: &str, item: &str) -> Option<i64> {
+        match self {
+            Matching::Prefix => item.starts_with(word).then_some(0),
+            Matching::IgnoreCase => {
+                let item: String = item.chars().take(word.chars().count()).collect();
+                (item.to_lowercase() == word.to_lowercase()).then_some(0)
+            }
+            Matching::Substring => {
+                let (word, item) = smart_case(word, item);
+                item.find(&word).map(|idx| -(idx as i64))
+            }
+            Matching::Fuzzy => fuzzy_score(word, item),
+        }
+    }
+
+    /// Whether items that differ from the word in case can match it.
+    pub fn ignores_case(self) -> bool {
+        self != Matching::Prefix
+    }
+}
+
+/// Case is ignored unless the word has an uppercase letter, as in fzf.
+fn smart_case(word: &str, item: &str) -> (String, String) {
+    if word.chars().any(char::is_uppercase) {
+        (word.to_owned(), item.to_owned())
+    } else {
+        (word.to_lowercase(), item.to_lowercase())
+    }
+}
+
+/// Scores `word` as a subsequence of `item`. Matched characters score more
+/// when they follow each other or start a word, gaps cost, less so before
+/// the first one.
+fn fuzzy_score(word: &str, item: &str) -> Option<i64> {
+    let (word, item) = smart_case(word, item);
+    let item: Vec<char> = item.chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+
+    for char in word.chars() {
+        let idx = (next..item.len()).find(|&idx| item[idx] == char)?;
+        score += MATCH_SCORE;
+        if idx > 0 && idx == next {
+            score += CONSECUTIVE_BONUS;
+        }
+        if idx == 0 || !item[idx - 1].is_alphanumeric() {
+            score += BOUNDARY_BONUS;
+        }
+        let penalty = if next == 0 {
+            LEADING_GAP_PENALTY
+        } else {
+            GAP_PENALTY
+        };
+        score -= (idx - next) as i64 * penalty;
+        next = idx + 1;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_is_case_sensitive() {
+        assert!(Matching::Prefix.matches("Ma", "Makefile"));
+        assert!(!Matching::Prefix.matches("ma", "Makefile"));
+        assert!(Matching::IgnoreCase.matches("ma", "Makefile"));
+        assert!(!Matching::IgnoreCase.matches("ke", "Makefile"));
+    }
+
+    #[test]
+    fn substring_prefers_earlier_matches() {
+        assert!(Matching::Substring.matches("file", "Makefile"));
+        assert!(!Matching::Substring.matches("File", "Makefile"));
+        assert!(
+            Matching::Substring.score("ma", "main.rs") > Matching::Substring.score("ma", "cmake")
+        );
+    }
+
+    #[test]
+    fn fuzzy_matches_subsequences() {
+        assert!(Matching::Fuzzy.matches("mkf", "Makefile"));
+        assert!(!Matching::Fuzzy.matches("fkm", "Makefile"));
+
+        // Consecutive and word start matches rank first
+        let score = |item| Matching::Fuzzy.score("lib", item).unwrap();
+        assert!(score("lib.rs") > score("src/lib.rs"));
+        assert!(score("src/lib.rs") > score("l_i_b"));
+        assert!(score("l_i_b") > score("linkbin"));
+    }
+}
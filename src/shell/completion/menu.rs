@@ -0,0 +1,104 @@
+use crate::shell::completion::columns;
+
+/// A match for the word under the cursor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MenuItem {
+    /// How the match is listed.
+    pub label: String,
+    /// The text the word is replaced with to complete the match.
+    pub replacement: String,
+}
+
+/// The matches listed on a second Tab, which Tab and Shift-Tab then cycle
+/// through.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompletionMenu {
+    items: Vec<MenuItem>,
+    selected: Option<usize>,
+}
+
+impl CompletionMenu {
+    pub fn new(items: Vec<MenuItem>) -> Self {
+        Self {
+            items,
+            selected: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn selected(&self) -> Option<&MenuItem> {
+        self.selected.and_then(|idx| self.items.get(idx))
+    }
+
+    /// Selects the next item, wrapping around to the first one.
+    pub fn select_next(&mut self) -> Option<&MenuItem> {
+        if self.items.is_empty() {
+            return None;
+        }
+        self.selected = Some(self.selected.map_or(0, |idx| (idx + 1) % self.items.len()));
+        self.selected()
+    }
+
+    /// Selects the previous item, wrapping around to the last one.
+    pub fn select_previous(&mut self) -> Option<&MenuItem> {
+        if self.items.is_empty() {
+            return None;
+        }
+        let last = self.items.len() - 1;
+        self.selected = Some(
+            self.selected
+                .map_or(last, |idx| idx.checked_sub(1).unwrap_or(last)),
+        );
+        self.selected()
+    }
+
+    /// The labels in columns, the selected one highlighted.
+    pub fn layout(&self, width: usize) -> String {
+        let labels: Vec<String> = self.items.iter().map(|item| item.label.clone()).collect();
+        columns(&labels, width, self.selected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn menu(labels: &[&str]) -> CompletionMenu {
+        CompletionMenu::new(
+            labels
+                .iter()
+                .map(|label| MenuItem {
+                    label: label.to_string(),
+                    replacement: format!("{label} "),
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn selection_wraps_around() {
+        let mut menu = menu(&["alpha", "beta"]);
+        assert_eq!(menu.selected(), None);
+        assert_eq!(menu.select_previous().unwrap().label, "beta");
+        assert_eq!(menu.select_next().unwrap().label, "alpha");
+        assert_eq!(menu.select_next().unwrap().label, "beta");
+        assert_eq!(menu.select_next().unwrap().label, "alpha");
+    }
+
+    #[test]
+    fn selected_label_is_highlighted() {
+        let mut menu = menu(&["alpha", "beta", "gamma"]);
+        assert_eq!(menu.layout(80), "alpha  beta  gamma");
+        menu.select_next();
+        menu.select_next();
+        assert_eq!(menu.layout(80), "alpha  \x1b[7mbeta\x1b[27m  gamma");
+        assert_eq!(menu.layout(14), "alpha  gamma\n\x1b[7mbeta\x1b[27m");
+    }
+}
@@ -1,78 +1,252 @@
-use std::sync::Arc;
+use std::{cmp::Reverse, fmt::Display, sync::Arc};
 
-use crate::shell::completion;
+use unicode_width::UnicodeWidthStr;
+
+use crate::shell::{
+    completion::{
+        context::{CompletionContext, Quoting},
+        matching::Matching,
+        menu::{CompletionMenu, MenuItem},
+    },
+    input::commons::{NORMAL_VIDEO, REVERSE_VIDEO},
+    terminal,
+};
 
 pub mod builtins;
+pub mod context;
+pub mod files;
+pub mod matching;
+pub mod menu;
 pub mod path_dirs;
+pub mod programmable;
+pub mod spec;
+pub mod variables;
+
+/// What a Tab press completes the word under the cursor to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Completed {
+    /// Text inserted at the cursor.
+    Insert(String),
+    /// Text the word under the cursor is replaced with, for matches that
+    /// don't start with it as typed.
+    Replace(String),
+    /// Every match, on a second Tab.
+    Menu(CompletionMenu),
+}
+
+impl Display for Completed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Completed::Insert(text) | Completed::Replace(text) => f.write_str(text),
+            Completed::Menu(menu) => f.write_str(&menu.layout(terminal::size().columns)),
+        }
+    }
+}
 
 pub(super) trait Completion {
-    fn completion_items(&self, args: &str) -> Vec<String>;
+    fn completion_items(&self, args: &str, matching: Matching) -> Vec<String>;
 
-    fn single_completion(&self, matches: Vec<String>, args: &str) -> Option<String> {
-        if matches.len() == 1 {
-            let completion_item = matches[0][args.len()..].to_string();
-            return Some(format!("{completion_item} "));
-        }
+    /// How well `item` matches the word, see [`Matching::score`].
+    fn score(&self, args: &str, item: &str, matching: Matching) -> Option<i64> {
+        matching.score(args, item)
+    }
 
-        let mut prefix = String::new();
+    /// Appended once an item is completed in full.
+    fn terminator(&self, _item: &str) -> &'static str {
+        " "
+    }
 
-        for (idx, char) in matches[0].chars().enumerate() {
-            let all_match = matches
-                .iter()
-                .all(|item| item.chars().nth(idx) == Some(char));
+    fn escape(&self, text: &str, quoting: Quoting, word_start: bool) -> String {
+        quoting.escape(text, word_start)
+    }
 
-            if !all_match {
-                break;
-            }
+    /// Closes an open quote and appends the terminator once `item` is
+    /// completed in full.
+    fn ending(&self, item: &str, quoting: Quoting) -> String {
+        let terminator = self.terminator(item);
+        if terminator.is_empty() {
+            return String::new();
+        }
+        format!("{}{terminator}", quoting.quote())
+    }
+
+    /// The whole word `item` is completed to, quoted like the typed word.
+    fn replacement(&self, item: &str, quoting: Quoting) -> String {
+        format!(
+            "{}{}{}",
+            quoting.quote(),
+            self.escape(item, quoting, true),
+            self.ending(item, quoting)
+        )
+    }
 
-            prefix.push(char);
+    /// Completes to the single match, or to the prefix all matches share.
+    /// Text that extends the word as typed is inserted, anything else,
+    /// such as a match in another case, replaces the word.
+    fn single_completion(
+        &self,
+        matches: Vec<String>,
+        args: &str,
+        quoting: Quoting,
+        matching: Matching,
+    ) -> Option<Completed> {
+        if matches.len() == 1 {
+            let item = &matches[0];
+            return Some(match item.strip_prefix(args) {
+                Some(rest) => Completed::Insert(format!(
+                    "{}{}",
+                    self.escape(rest, quoting, args.is_empty()),
+                    self.ending(item, quoting)
+                )),
+                None => Completed::Replace(self.replacement(item, quoting)),
+            });
         }
 
-        if prefix.len() <= args.len() {
+        let prefix = common_prefix(&matches, matching.ignores_case());
+        if prefix.chars().count() <= args.chars().count()
+            || self.score(args, &prefix, matching).is_none()
+        {
             return None;
         }
 
-        Some(prefix[args.len()..].to_string())
+        Some(match prefix.strip_prefix(args) {
+            Some(rest) => Completed::Insert(self.escape(rest, quoting, args.is_empty())),
+            None => Completed::Replace(format!(
+                "{}{}",
+                quoting.quote(),
+                self.escape(&prefix, quoting, true)
+            )),
+        })
+    }
+
+    /// How an item is listed in the menu.
+    fn label(&self, item: &str) -> String {
+        item.to_owned()
     }
 
-    fn multiple_completion(&self, matches: Vec<String>) -> Option<String> {
-        Some(matches.join("  "))
+    fn multiple_completion(&self, matches: Vec<String>, quoting: Quoting) -> Completed {
+        let items = matches
+            .iter()
+            .map(|item| MenuItem {
+                label: self.label(item),
+                replacement: self.replacement(item, quoting),
+            })
+            .collect();
+        Completed::Menu(CompletionMenu::new(items))
     }
 
-    fn complete(&self, args: &str, multiple: bool) -> Option<String> {
+    fn complete(&self, args: &str, matching: Matching, multiple: bool) -> Option<Completed> {
         if args.is_empty() {
             return None;
         }
+        self.complete_quoted(args, Quoting::Unquoted, matching, multiple)
+    }
+
+    /// Completes the word of `context`, an empty word matches every item.
+    fn complete_word(&self, context: &CompletionContext, multiple: bool) -> Option<Completed> {
+        self.complete_quoted(
+            context.word(),
+            context.quoting(),
+            context.matching(),
+            multiple,
+        )
+    }
 
-        let mut matches = self.completion_items(args);
+    /// Matches are looked up with the unquoted word, the text inserted is
+    /// escaped for `quoting`. The best matches come first.
+    fn complete_quoted(
+        &self,
+        args: &str,
+        quoting: Quoting,
+        matching: Matching,
+        multiple: bool,
+    ) -> Option<Completed> {
+        let mut matches = self.completion_items(args, matching);
 
         if matches.is_empty() {
             return None;
         }
 
-        matches.sort();
+        matches
+            .sort_by_cached_key(|item| (Reverse(self.score(args, item, matching)), item.clone()));
         if multiple {
-            return self.multiple_completion(matches);
+            return Some(self.multiple_completion(matches, quoting));
         }
 
-        return self.single_completion(matches, args);
+        self.single_completion(matches, args, quoting, matching)
     }
 }
 
+/// The longest prefix of all `items`, spelled like the first one.
+fn common_prefix(items: &[String], ignore_case: bool) -> String {
+    let same = |a: char, b: char| {
+        if ignore_case {
+            a.to_lowercase().eq(b.to_lowercase())
+        } else {
+            a == b
+        }
+    };
+    let mut prefix = String::new();
+
+    for (idx, char) in items[0].chars().enumerate() {
+        if !items
+            .iter()
+            .all(|item| item.chars().nth(idx).is_some_and(|other| same(char, other)))
+        {
+            break;
+        }
+        prefix.push(char);
+    }
+    prefix
+}
+
+/// Lays out items in columns sorted top to bottom, like `ls`. Items that
+/// fit on a single row are just separated by two spaces. The `selected`
+/// item is shown in reverse video.
+pub(crate) fn columns(items: &[String], width: usize, selected: Option<usize>) -> String {
+    let widths: Vec<usize> = items.iter().map(|item| item.width()).collect();
+    let label = |idx: usize| match selected {
+        Some(selected) if selected == idx => format!("{REVERSE_VIDEO}{}{NORMAL_VIDEO}", items[idx]),
+        _ => items[idx].clone(),
+    };
+    if widths.iter().sum::<usize>() + 2 * items.len().saturating_sub(1) <= width {
+        return (0..items.len()).map(label).collect::<Vec<_>>().join("  ");
+    }
+
+    let column_width = widths.iter().max().copied().unwrap_or_default() + 2;
+    let per_row = (width / column_width).max(1);
+    let rows = items.len().div_ceil(per_row);
+
+    (0..rows)
+        .map(|row| {
+            let cells: Vec<usize> = (row..items.len()).step_by(rows).collect();
+            let mut line = String::new();
+            for (idx, &cell) in cells.iter().enumerate() {
+                line.push_str(&label(cell));
+                if idx + 1 < cells.len() {
+                    line.push_str(&" ".repeat(column_width - widths[cell]));
+                }
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 pub trait CompletionComponent {
-    fn execute(&self, input: &str, multiple: bool) -> Option<String> {
-        match self.handler(input, multiple) {
+    fn execute(&self, context: &CompletionContext, multiple: bool) -> Option<Completed> {
+        match self.handler(context, multiple) {
             Some(res) => Some(res),
             None => {
                 if let Some(next) = self.next() {
-                    return next.execute(input, multiple);
+                    return next.execute(context, multiple);
                 }
                 None
             }
         }
     }
 
-    fn handler(&self, args: &str, multiple: bool) -> Option<String>;
+    fn handler(&self, context: &CompletionContext, multiple: bool) -> Option<Completed>;
 
     fn next(&self) -> Option<Arc<dyn CompletionComponent>>;
 }
@@ -86,10 +260,10 @@ mod tests {
     }
 
     impl Completion for MockCompletion {
-        fn completion_items(&self, args: &str) -> Vec<String> {
+        fn completion_items(&self, args: &str, matching: Matching) -> Vec<String> {
             self.items
                 .iter()
-                .filter(|item| item.starts_with(args))
+                .filter(|item| matching.matches(args, item))
                 .cloned()
                 .collect()
         }
@@ -100,7 +274,7 @@ mod tests {
         let comp = MockCompletion {
             items: vec!["echo".to_string(), "exit".to_string()],
         };
-        assert_eq!(comp.complete("xyz", false), None);
+        assert_eq!(comp.complete("xyz", Matching::Prefix, false), None);
     }
 
     #[test]
@@ -109,7 +283,10 @@ mod tests {
             items: vec!["echo_test".to_string()],
         };
         // "ech" should complete to "o_test "
-        assert_eq!(comp.complete("ech", false), Some("o_test ".to_string()));
+        assert_eq!(
+            comp.complete("ech", Matching::Prefix, false),
+            Some(Completed::Insert("o_test ".to_string()))
+        );
     }
 
     #[test]
@@ -118,7 +295,10 @@ mod tests {
             items: vec!["echo_test".to_string(), "echo_debug".to_string()],
         };
         // "ech" should complete to "o_" (common prefix)
-        assert_eq!(comp.complete("ech", false), Some("o_".to_string()));
+        assert_eq!(
+            comp.complete("ech", Matching::Prefix, false),
+            Some(Completed::Insert("o_".to_string()))
+        );
     }
 
     #[test]
@@ -127,7 +307,7 @@ mod tests {
             items: vec!["echo".to_string(), "exit".to_string()],
         };
         // "e" has no common prefix beyond itself
-        assert_eq!(comp.complete("e", false), None);
+        assert_eq!(comp.complete("e", Matching::Prefix, false), None);
     }
 
     #[test]
@@ -137,7 +317,8 @@ mod tests {
         };
         // Should return all matches sorted
         assert_eq!(
-            comp.complete("e", true),
+            comp.complete("e", Matching::Prefix, true)
+                .map(|completed| completed.to_string()),
             Some("echo  env  exit".to_string())
         );
     }
@@ -148,7 +329,10 @@ mod tests {
             items: vec!["echo".to_string()],
         };
         // Already fully typed "echo" - nothing to add
-        assert_eq!(comp.complete("echo", false), Some(" ".to_string()));
+        assert_eq!(
+            comp.complete("echo", Matching::Prefix, false),
+            Some(Completed::Insert(" ".to_string()))
+        );
     }
 
     #[test]
@@ -160,9 +344,15 @@ mod tests {
                 "apply".to_string(),
             ],
         };
-        assert_eq!(comp.complete("app", false), Some("l".to_string()));
-        assert_eq!(comp.complete("appl", false), None);
-        assert_eq!(comp.complete("appli", false), Some("cation ".to_string()));
+        assert_eq!(
+            comp.complete("app", Matching::Prefix, false),
+            Some(Completed::Insert("l".to_string()))
+        );
+        assert_eq!(comp.complete("appl", Matching::Prefix, false), None);
+        assert_eq!(
+            comp.complete("appli", Matching::Prefix, false),
+            Some(Completed::Insert("cation ".to_string()))
+        );
     }
 
     #[test]
@@ -171,7 +361,7 @@ mod tests {
             items: vec!["echo".to_string(), "exit".to_string()],
         };
         // Empty input, multiple matches, no common prefix
-        assert_eq!(comp.complete("", false), None);
+        assert_eq!(comp.complete("", Matching::Prefix, false), None);
     }
 
     #[test]
@@ -180,7 +370,120 @@ mod tests {
             items: vec!["test_one".to_string(), "test_two".to_string()],
         };
         // "t" should complete to "est_" (common prefix)
-        assert_eq!(comp.complete("t", false), Some("est_".to_string()));
+        assert_eq!(
+            comp.complete("t", Matching::Prefix, false),
+            Some(Completed::Insert("est_".to_string()))
+        );
+    }
+
+    #[test]
+    fn quoted_completion_is_escaped_and_closed() {
+        let comp = MockCompletion {
+            items: vec!["it's here".to_string(), "it's there".to_string()],
+        };
+        assert_eq!(
+            comp.complete_quoted("it", Quoting::Unquoted, Matching::Prefix, false),
+            Some(Completed::Insert("\\'s\\ ".to_string()))
+        );
+        assert_eq!(
+            comp.complete_quoted("it's h", Quoting::Single, Matching::Prefix, false),
+            Some(Completed::Insert("ere' ".to_string()))
+        );
+        assert_eq!(
+            comp.complete_quoted("it", Quoting::Double, Matching::Prefix, true)
+                .map(|completed| completed.to_string()),
+            Some("it's here  it's there".to_string())
+        );
+    }
+
+    #[test]
+    fn menu_items_replace_the_word() {
+        let comp = MockCompletion {
+            items: vec!["echo".to_string(), "exit".to_string()],
+        };
+        let Some(Completed::Menu(mut menu)) = comp.complete("e", Matching::Prefix, true) else {
+            panic!("expected a menu");
+        };
+        assert_eq!(menu.len(), 2);
+        assert_eq!(
+            menu.select_previous(),
+            Some(&MenuItem {
+                label: "exit".to_string(),
+                replacement: "exit ".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn common_prefix_ignores_case() {
+        let comp = MockCompletion {
+            items: vec![
+                "Makefile".to_string(),
+                "MakeRules".to_string(),
+                "makefile.bak".to_string(),
+            ],
+        };
+        assert_eq!(
+            comp.complete("mak", Matching::Prefix, false),
+            Some(Completed::Insert("efile.bak ".to_string()))
+        );
+        // Spelled like the first match, replacing the word typed in lowercase
+        assert_eq!(
+            comp.complete("mak", Matching::IgnoreCase, false),
+            Some(Completed::Replace("Make".to_string()))
+        );
+        assert_eq!(comp.complete("Make", Matching::IgnoreCase, false), None);
+        assert_eq!(
+            comp.complete("Makef", Matching::IgnoreCase, false),
+            Some(Completed::Insert("ile".to_string()))
+        );
+        assert_eq!(
+            comp.complete("makeR", Matching::IgnoreCase, false),
+            Some(Completed::Replace("MakeRules ".to_string()))
+        );
+    }
+
+    #[test]
+    fn fuzzy_matches_are_ranked() {
+        let comp = MockCompletion {
+            items: vec![
+                "libc.so".to_string(),
+                "lib.rs".to_string(),
+                "Cargo.lock".to_string(),
+            ],
+        };
+        assert_eq!(
+            comp.complete("crgl", Matching::Fuzzy, false),
+            Some(Completed::Replace("Cargo.lock ".to_string()))
+        );
+        // The `s` of `.so` starts a word, which ranks it first
+        assert_eq!(
+            comp.complete("ls", Matching::Fuzzy, true)
+                .map(|completed| completed.to_string()),
+            Some("libc.so  lib.rs".to_string())
+        );
+    }
+
+    #[test]
+    fn long_listings_are_laid_out_in_columns() {
+        let items: Vec<String> = ["alpha", "bravo", "charlie", "delta", "echo"]
+            .iter()
+            .map(|item| item.to_string())
+            .collect();
+
+        assert_eq!(
+            columns(&items, 80, None),
+            "alpha  bravo  charlie  delta  echo"
+        );
+        assert_eq!(
+            columns(&items, 30, None),
+            "alpha    charlie  echo\nbravo    delta"
+        );
+        assert_eq!(
+            columns(&items, 20, None),
+            "alpha    delta\nbravo    echo\ncharlie"
+        );
+        assert_eq!(columns(&items, 5, None), items.join("\n"));
     }
 
     #[test]
@@ -190,7 +493,8 @@ mod tests {
         };
         // Should be alphabetically sorted
         assert_eq!(
-            comp.complete("a", true),
+            comp.complete("a", Matching::Prefix, true)
+                .map(|completed| completed.to_string()),
             Some("abbd  abcd  abdc".to_string())
         );
     }
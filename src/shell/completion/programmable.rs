@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use crate::shell::{
+    completion::{
+        context::{CompletionContext, WordPosition},
+        files::FileCompletion,
+        matching::Matching,
+        path_dirs::PathDirsCompletion,
+        spec::{CompletionSpec, CompletionSpecs},
+        Completed, Completion, CompletionComponent,
+    },
+    path::PathDirsProvider,
+};
+
+/// Completes the arguments of commands with a `complete` spec. Words the
+/// spec has no candidate for fall back to the default completion.
+pub struct ProgrammableCompletion {
+    specs: Arc<CompletionSpecs>,
+    path_dirs: Arc<PathDirsProvider>,
+    next: Arc<PathDirsCompletion>,
+}
+
+impl ProgrammableCompletion {
+    pub fn new(specs: Arc<CompletionSpecs>, path_dirs: Arc<PathDirsProvider>) -> Self {
+        let next = Arc::new(PathDirsCompletion::new(Arc::clone(&path_dirs)));
+        Self {
+            specs,
+            path_dirs,
+            next,
+        }
+    }
+}
+
+/// The candidates of a spec, inserted the way the spec's options say.
+struct SpecMatches {
+    spec: CompletionSpec,
+    items: Vec<String>,
+}
+
+impl Completion for SpecMatches {
+    fn completion_items(&self, args: &str, matching: Matching) -> Vec<String> {
+        let mut items: Vec<String> = self
+            .items
+            .iter()
+            .filter(|item| matching.matches(args, item))
+            .cloned()
+            .collect();
+        items.sort();
+        items.dedup();
+        items
+    }
+
+    fn score(&self, args: &str, item: &str, matching: Matching) -> Option<i64> {
+        if self.spec.filenames() {
+            return FileCompletion::default().score(args, item, matching);
+        }
+        matching.score(args, item)
+    }
+
+    fn terminator(&self, item: &str) -> &'static str {
+        self.spec.terminator(item)
+    }
+
+    fn label(&self, item: &str) -> String {
+        if self.spec.filenames() {
+            return FileCompletion::default().label(item);
+        }
+        item.to_owned()
+    }
+}
+
+impl CompletionComponent for ProgrammableCompletion {
+    fn handler(&self, context: &CompletionContext, multiple: bool) -> Option<Completed> {
+        if !matches!(context.position(), WordPosition::Argument(_)) {
+            return None;
+        }
+        let spec = self.specs.get(context.command()?)?;
+        let items = spec.candidates(context, &self.path_dirs);
+        SpecMatches { spec, items }.complete_word(context, multiple)
+    }
+
+    fn next(&self) -> Option<Arc<dyn CompletionComponent>> {
+        Some(self.next.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup(args: &[&str]) -> ProgrammableCompletion {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let specs = Arc::new(CompletionSpecs::default());
+        specs.set(
+            "svc",
+            CompletionSpec::parse("complete", &args).unwrap().spec,
+        );
+        ProgrammableCompletion::new(specs, Arc::new(PathDirsProvider::new(Vec::new())))
+    }
+
+    #[test]
+    fn completes_arguments_from_the_spec() {
+        let completion = setup(&["-W", "start stop status"]);
+
+        let context = CompletionContext::parse("svc sto");
+        assert_eq!(
+            completion.handler(&context, false),
+            Some(Completed::Insert("p ".to_string()))
+        );
+        let context = CompletionContext::parse("svc st");
+        assert_eq!(completion.handler(&context, false), None);
+        assert_eq!(
+            completion
+                .handler(&context, true)
+                .map(|completed| completed.to_string()),
+            Some("start  status  stop".to_string())
+        );
+    }
+
+    #[test]
+    fn nospace_leaves_the_word_open() {
+        let completion = setup(&["-o", "nospace", "-W", "--name="]);
+
+        let context = CompletionContext::parse("svc --na");
+        assert_eq!(
+            completion.handler(&context, false),
+            Some(Completed::Insert("me=".to_string()))
+        );
+    }
+
+    #[test]
+    fn other_commands_are_left_to_the_chain() {
+        let completion = setup(&["-W", "start"]);
+
+        assert_eq!(
+            completion.handler(&CompletionContext::parse("cat st"), false),
+            None
+        );
+        assert_eq!(
+            completion.handler(&CompletionContext::parse("sv"), false),
+            None
+        );
+    }
+}
@@ -0,0 +1,380 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, RwLock},
+};
+
+use crate::{
+    exceptions::{commands::ShellError, completion_error::CompletionError},
+    external::ExternalCommand,
+    port::{command::CommandResult, shell_component::ShellComponent},
+    shell::{
+        completion::{
+            builtins::BuiltinsCompletion, context::CompletionContext, files::FileCompletion,
+            path_dirs::PathDirsCompletion, Completion,
+        },
+        path::PathDirsProvider,
+    },
+};
+
+/// How the arguments of a command are completed, as set by `complete`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompletionSpec {
+    words: Option<String>,
+    command: Option<String>,
+    directories: bool,
+    files: bool,
+    commands: bool,
+    nospace: bool,
+    filenames: bool,
+}
+
+/// Options and operands given to `complete` or `compgen`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SpecArgs {
+    pub spec: CompletionSpec,
+    pub print: bool,
+    pub remove: bool,
+    pub operands: Vec<String>,
+}
+
+impl CompletionSpec {
+    /// Parses the options of `builtin` up to the first operand. Flags can
+    /// be grouped, as in `-df` or `-oW'a b'`.
+    pub fn parse(builtin: &str, args: &[String]) -> Result<SpecArgs, ShellError> {
+        let mut parsed = SpecArgs::default();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            if arg == "--" {
+                break;
+            }
+            if !arg.starts_with('-') || arg.len() == 1 {
+                parsed.operands.push(arg.clone());
+                break;
+            }
+
+            for (idx, flag) in arg.char_indices().skip(1) {
+                match flag {
+                    'd' => parsed.spec.directories = true,
+                    'f' => parsed.spec.files = true,
+                    'c' => parsed.spec.commands = true,
+                    'p' => parsed.print = true,
+                    'r' => parsed.remove = true,
+                    'F' => {
+                        return Err(CompletionError::FunctionUnsupported(builtin.to_owned()).into())
+                    }
+                    'W' | 'C' | 'o' => {
+                        let value = match &arg[idx + 1..] {
+                            "" => args.next().cloned(),
+                            rest => Some(rest.to_owned()),
+                        }
+                        .ok_or_else(|| {
+                            ShellError::MissingOptionArgument(format!("{builtin}: -{flag}"))
+                        })?;
+                        parsed.spec.set(builtin, flag, value)?;
+                        break;
+                    }
+                    _ => return Err(ShellError::InvalidOption(format!("{builtin}: -{flag}"))),
+                }
+            }
+        }
+
+        parsed.operands.extend(args.cloned());
+        Ok(parsed)
+    }
+
+    fn set(&mut self, builtin: &str, flag: char, value: String) -> Result<(), ShellError> {
+        match (flag, value.as_str()) {
+            ('W', _) => self.words = Some(value),
+            ('C', _) => self.command = Some(value),
+            (_, "nospace") => self.nospace = true,
+            (_, "filenames") => self.filenames = true,
+            _ => return Err(ShellError::InvalidOption(format!("{builtin}: {value}"))),
+        }
+        Ok(())
+    }
+
+    /// The `complete` command that defines this spec for `name`.
+    pub fn to_command(&self, name: &str) -> String {
+        let quote = |value: &str| format!("'{}'", value.replace('\'', "'\\''"));
+        let mut parts = vec!["complete".to_owned()];
+
+        for (set, option) in [
+            (self.nospace, "-o nospace"),
+            (self.filenames, "-o filenames"),
+            (self.commands, "-c"),
+            (self.directories, "-d"),
+            (self.files, "-f"),
+        ] {
+            if set {
+                parts.push(option.to_owned());
+            }
+        }
+        if let Some(words) = &self.words {
+            parts.push(format!("-W {}", quote(words)));
+        }
+        if let Some(command) = &self.command {
+            parts.push(format!("-C {}", quote(command)));
+        }
+        parts.push(name.to_owned());
+        parts.join(" ")
+    }
+
+    /// Candidates for the word under the cursor, in the order bash lists
+    /// them. The output of `-C` is taken as is.
+    pub fn candidates(
+        &self,
+        context: &CompletionContext,
+        path_dirs: &Arc<PathDirsProvider>,
+    ) -> Vec<String> {
+        let word = context.word();
+        let matching = context.matching();
+        let mut items = Vec::new();
+
+        if self.commands {
+            let mut commands =
+                BuiltinsCompletion::new(Arc::clone(path_dirs)).completion_items(word, matching);
+            commands.extend(
+                PathDirsCompletion::new(Arc::clone(path_dirs)).completion_items(word, matching),
+            );
+            commands.sort();
+            commands.dedup();
+            items.extend(commands);
+        }
+        if self.directories || self.files {
+            let files = if self.files {
+                FileCompletion::default()
+            } else {
+                FileCompletion::directories()
+            };
+            let mut paths = files.completion_items(word, matching);
+            paths.sort();
+            items.extend(paths.into_iter().map(|path| {
+                if self.filenames {
+                    path
+                } else {
+                    path.trim_end_matches('/').to_owned()
+                }
+            }));
+        }
+        if let Some(words) = &self.words {
+            items.extend(
+                words
+                    .split_whitespace()
+                    .filter(|item| matching.matches(word, item))
+                    .map(str::to_owned),
+            );
+        }
+        if let Some(command) = &self.command {
+            items.extend(Self::generate(command, context, path_dirs));
+        }
+        items
+    }
+
+    /// Runs a generator with the command name, the word and the previous
+    /// word as arguments, and `COMP_LINE`/`COMP_POINT` set for it.
+    fn generate(
+        generator: &str,
+        context: &CompletionContext,
+        path_dirs: &Arc<PathDirsProvider>,
+    ) -> Vec<String> {
+        let mut words = generator.split_whitespace();
+        let Some(program) = words.next() else {
+            return Vec::new();
+        };
+        let mut args: Vec<String> = words.map(str::to_owned).collect();
+        args.extend(
+            [
+                context.command(),
+                Some(context.word()),
+                context.previous_word(),
+            ]
+            .map(|arg| arg.unwrap_or_default().to_owned()),
+        );
+
+        let output = ExternalCommand::new(Arc::clone(path_dirs))
+            .with_env("COMP_LINE", context.line())
+            .with_env("COMP_POINT", &context.line().chars().count().to_string())
+            .handler(program, &args);
+
+        match output {
+            Ok(CommandResult::Stdio(stdout, _) | CommandResult::Failure(stdout, _, _)) => {
+                stdout.lines().map(str::to_owned).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Appended once an item is completed in full.
+    pub fn terminator(&self, item: &str) -> &'static str {
+        if self.nospace || (self.filenames && item.ends_with('/')) {
+            ""
+        } else {
+            " "
+        }
+    }
+
+    pub fn filenames(&self) -> bool {
+        self.filenames
+    }
+}
+
+/// Completion specs by command name.
+#[derive(Debug, Default)]
+pub struct CompletionSpecs {
+    specs: RwLock<BTreeMap<String, CompletionSpec>>,
+}
+
+impl CompletionSpecs {
+    pub fn get(&self, name: &str) -> Option<CompletionSpec> {
+        self.specs
+            .read()
+            .ok()
+            .and_then(|specs| specs.get(name).cloned())
+    }
+
+    pub fn set(&self, name: &str, spec: CompletionSpec) {
+        if let Ok(mut specs) = self.specs.write() {
+            specs.insert(name.to_owned(), spec);
+        }
+    }
+
+    pub fn remove(&self, name: &str) -> Option<CompletionSpec> {
+        self.specs
+            .write()
+            .ok()
+            .and_then(|mut specs| specs.remove(name))
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut specs) = self.specs.write() {
+            specs.clear();
+        }
+    }
+
+    /// All specs sorted by command name.
+    pub fn list(&self) -> Vec<(String, CompletionSpec)> {
+        self.specs
+            .read()
+            .map(|specs| {
+                specs
+                    .iter()
+                    .map(|(name, spec)| (name.clone(), spec.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_options_and_operands() {
+        let parsed = CompletionSpec::parse(
+            "complete",
+            &args(&["-W", "start stop", "-o", "nospace", "-df", "svc", "-x"]),
+        )
+        .unwrap();
+
+        assert_eq!(parsed.spec.words, Some("start stop".to_owned()));
+        assert!(parsed.spec.nospace && parsed.spec.directories && parsed.spec.files);
+        assert_eq!(parsed.operands, args(&["svc", "-x"]));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            CompletionSpec::parse("complete", &args(&["-W"])),
+            Err(ShellError::MissingOptionArgument("complete: -W".to_owned()))
+        );
+        assert_eq!(
+            CompletionSpec::parse("compgen", &args(&["-z"])),
+            Err(ShellError::InvalidOption("compgen: -z".to_owned()))
+        );
+        assert_eq!(
+            CompletionSpec::parse("complete", &args(&["-o", "bogus", "x"])),
+            Err(ShellError::InvalidOption("complete: bogus".to_owned()))
+        );
+        assert_eq!(
+            CompletionSpec::parse("complete", &args(&["-F", "_svc", "svc"])),
+            Err(CompletionError::FunctionUnsupported("complete".to_owned()).into())
+        );
+    }
+
+    #[test]
+    fn print_as_complete_command() {
+        let parsed = CompletionSpec::parse(
+            "complete",
+            &args(&["-C", "gen it's", "-W", "a b", "-o", "filenames", "-d"]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            parsed.spec.to_command("svc"),
+            "complete -o filenames -d -W 'a b' -C 'gen it'\\''s' svc"
+        );
+    }
+
+    #[test]
+    fn wordlist_candidates_match_the_word() {
+        let spec = CompletionSpec::parse("compgen", &args(&["-W", "start stop status"]))
+            .unwrap()
+            .spec;
+        let path_dirs = Arc::new(PathDirsProvider::new(Vec::new()));
+
+        assert_eq!(
+            spec.candidates(&CompletionContext::argument("svc", "st"), &path_dirs),
+            args(&["start", "stop", "status"])
+        );
+        assert_eq!(
+            spec.candidates(&CompletionContext::argument("svc", "sta"), &path_dirs),
+            args(&["start", "status"])
+        );
+    }
+
+    #[test]
+    fn directories_end_with_slash_only_for_filenames() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("script"), "").unwrap();
+        let word = format!("{}/s", dir.path().to_str().unwrap());
+        let context = CompletionContext::argument("svc", &word);
+        let path_dirs = Arc::new(PathDirsProvider::new(Vec::new()));
+
+        let spec = CompletionSpec::parse("compgen", &args(&["-d"]))
+            .unwrap()
+            .spec;
+        assert_eq!(
+            spec.candidates(&context, &path_dirs),
+            vec![format!("{}rc", word)]
+        );
+
+        let spec = CompletionSpec::parse("compgen", &args(&["-f", "-o", "filenames"]))
+            .unwrap()
+            .spec;
+        assert_eq!(
+            spec.candidates(&context, &path_dirs),
+            vec![format!("{word}cript"), format!("{word}rc/")]
+        );
+        assert_eq!(spec.terminator("src/"), "");
+        assert_eq!(spec.terminator("script"), " ");
+    }
+
+    #[test]
+    fn store_lists_specs_by_name() {
+        let specs = CompletionSpecs::default();
+        specs.set("svc", CompletionSpec::default());
+        specs.set("app", CompletionSpec::default());
+
+        let names: Vec<String> = specs.list().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, args(&["app", "svc"]));
+        assert!(specs.remove("svc").is_some());
+        assert_eq!(specs.get("svc"), None);
+    }
+}
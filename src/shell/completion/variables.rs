@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use crate::shell::completion::{
+    context::{CompletionContext, Quoting},
+    files::FileCompletion,
+    matching::Matching,
+    Completed, Completion, CompletionComponent,
+};
+
+/// Completes environment variable names, keeping the `$` when the word
+/// has one.
+pub struct VariableCompletion {
+    next: Arc<FileCompletion>,
+}
+
+impl Default for VariableCompletion {
+    fn default() -> Self {
+        Self {
+            next: Arc::new(FileCompletion::default()),
+        }
+    }
+}
+
+impl VariableCompletion {
+    /// Splits the `$`, if any, from the name.
+    fn split(word: &str) -> (&str, &str) {
+        match word.strip_prefix('$') {
+            Some(name) => ("$", name),
+            None => ("", word),
+        }
+    }
+}
+
+impl Completion for VariableCompletion {
+    fn completion_items(&self, word: &str, matching: Matching) -> Vec<String> {
+        let (sigil, prefix) = Self::split(word);
+
+        std::env::vars_os()
+            .filter_map(|(name, _)| name.into_string().ok())
+            .filter(|name| matching.matches(prefix, name))
+            .map(|name| format!("{sigil}{name}"))
+            .collect()
+    }
+
+    fn score(&self, word: &str, item: &str, matching: Matching) -> Option<i64> {
+        matching.score(Self::split(word).1, Self::split(item).1)
+    }
+
+    /// The `$` of a replaced word is kept as is.
+    fn escape(&self, text: &str, quoting: Quoting, word_start: bool) -> String {
+        match text.strip_prefix('$') {
+            Some(name) => format!("${}", quoting.escape(name, false)),
+            None => quoting.escape(text, word_start),
+        }
+    }
+}
+
+impl CompletionComponent for VariableCompletion {
+    fn handler(&self, context: &CompletionContext, multiple: bool) -> Option<Completed> {
+        if !context.expects_variable() {
+            return None;
+        }
+        self.complete(context.word(), context.matching(), multiple)
+    }
+
+    fn next(&self) -> Option<Arc<dyn CompletionComponent>> {
+        Some(self.next.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn complete_variable_names() {
+        std::env::set_var("COMPLETION_TEST_VARIABLE", "1");
+        let completion = VariableCompletion::default();
+
+        assert_eq!(
+            completion.complete("$COMPLETION_TEST_VA", Matching::Prefix, false),
+            Some(Completed::Insert("RIABLE ".to_string()))
+        );
+        assert_eq!(
+            completion.complete("COMPLETION_TEST_VA", Matching::Prefix, false),
+            Some(Completed::Insert("RIABLE ".to_string()))
+        );
+    }
+}
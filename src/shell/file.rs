@@ -40,7 +40,7 @@ impl FileManager {
 
     pub fn create_file_if_no_exist(&self, path: &PathBuf) -> Result<(), ShellError> {
         if !path.exists() {
-            return self.create_file(&path);
+            return self.create_file(path);
         }
         Ok(())
     }
@@ -0,0 +1,378 @@
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        RwLock,
+    },
+};
+
+use crate::exceptions::history_error::HistoryError;
+
+const DEFAULT_HISTFILE: &str = ".shell_history";
+const DEFAULT_HISTSIZE: usize = 500;
+
+#[derive(Debug)]
+pub struct History {
+    entries: RwLock<Vec<String>>,
+    /// Index of the first entry that has not been appended to a file yet.
+    unsaved: AtomicUsize,
+    file: Option<PathBuf>,
+    size: usize,
+    file_size: usize,
+    ignore_dups: bool,
+    ignore_space: bool,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new(None, DEFAULT_HISTSIZE, DEFAULT_HISTSIZE)
+    }
+}
+
+impl History {
+    pub fn new(file: Option<PathBuf>, size: usize, file_size: usize) -> Self {
+        Self {
+            entries: RwLock::new(Vec::new()),
+            unsaved: AtomicUsize::new(0),
+            file,
+            size,
+            file_size,
+            ignore_dups: false,
+            ignore_space: false,
+        }
+    }
+
+    /// Reads `HISTFILE`, `HISTSIZE`, `HISTFILESIZE` and `HISTCONTROL`.
+    pub fn from_env() -> Self {
+        let file = std::env::var_os("HISTFILE")
+            .map(PathBuf::from)
+            .or_else(|| std::env::home_dir().map(|home| home.join(DEFAULT_HISTFILE)));
+        let size = Self::env_size("HISTSIZE").unwrap_or(DEFAULT_HISTSIZE);
+        let file_size = Self::env_size("HISTFILESIZE").unwrap_or(size);
+
+        Self::new(file, size, file_size)
+            .with_control(&std::env::var("HISTCONTROL").unwrap_or_default())
+    }
+
+    /// Applies a colon separated `HISTCONTROL` value.
+    pub fn with_control(mut self, control: &str) -> Self {
+        for value in control.split(':') {
+            match value {
+                "ignoredups" => self.ignore_dups = true,
+                "ignorespace" => self.ignore_space = true,
+                "ignoreboth" => {
+                    self.ignore_dups = true;
+                    self.ignore_space = true;
+                }
+                _ => {}
+            }
+        }
+        self
+    }
+
+    fn env_size(name: &str) -> Option<usize> {
+        std::env::var(name).ok()?.trim().parse().ok()
+    }
+
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    /// Records a line, returning whether it was kept.
+    pub fn add(&self, line: &str) -> bool {
+        let line = line.trim_end_matches(['\n', '\r']);
+        if line.trim().is_empty() || (self.ignore_space && line.starts_with(' ')) {
+            return false;
+        }
+
+        let Ok(mut entries) = self.entries.write() else {
+            return false;
+        };
+        if self.ignore_dups && entries.last().is_some_and(|last| last == line) {
+            return false;
+        }
+
+        entries.push(line.to_owned());
+        self.truncate(&mut entries);
+        true
+    }
+
+    pub fn entries(&self) -> Vec<String> {
+        self.entries
+            .read()
+            .map(|entries| entries.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn get(&self, index: usize) -> Option<String> {
+        self.entries
+            .read()
+            .ok()
+            .and_then(|entries| entries.get(index).cloned())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries
+            .read()
+            .map(|entries| entries.len())
+            .unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut entries) = self.entries.write() {
+            entries.clear();
+        }
+        self.unsaved.store(0, Ordering::Relaxed);
+    }
+
+    pub fn delete(&self, index: usize) -> Option<String> {
+        let mut entries = self.entries.write().ok()?;
+        if index >= entries.len() {
+            return None;
+        }
+
+        if index < self.unsaved.load(Ordering::Relaxed) {
+            self.unsaved.fetch_sub(1, Ordering::Relaxed);
+        }
+        Some(entries.remove(index))
+    }
+
+    /// Loads the configured history file, if it exists.
+    pub fn load(&self) -> Result<(), HistoryError> {
+        match self.file() {
+            Some(file) if file.is_file() => self.read_file(file),
+            _ => Ok(()),
+        }
+    }
+
+    /// Appends the entries of this session to the configured history file.
+    pub fn save(&self) -> Result<(), HistoryError> {
+        match self.file() {
+            Some(file) => self.append_file(file),
+            None => Ok(()),
+        }
+    }
+
+    /// Reads the entries of `path` in before those of this session that
+    /// haven't been saved yet, which stay unsaved.
+    pub fn read_file(&self, path: &Path) -> Result<(), HistoryError> {
+        let content = std::fs::read_to_string(path).map_err(|err| Self::io_error(path, err))?;
+        let read: Vec<String> = content
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(Self::unescape)
+            .collect();
+
+        let Ok(mut entries) = self.entries.write() else {
+            return Ok(());
+        };
+        let unsaved = self.unsaved.load(Ordering::Relaxed).min(entries.len());
+        self.unsaved.store(unsaved + read.len(), Ordering::Relaxed);
+        entries.splice(unsaved..unsaved, read);
+        self.truncate(&mut entries);
+        Ok(())
+    }
+
+    pub fn write_file(&self, path: &Path) -> Result<(), HistoryError> {
+        let entries = self.entries();
+        let start = entries.len().saturating_sub(self.file_size);
+        let lines: Vec<String> = entries[start..]
+            .iter()
+            .map(String::as_str)
+            .map(Self::escape)
+            .collect();
+        std::fs::write(path, Self::join(&lines)).map_err(|err| Self::io_error(path, err))?;
+
+        self.unsaved.store(entries.len(), Ordering::Relaxed);
+        Ok(())
+    }
+
+    pub fn append_file(&self, path: &Path) -> Result<(), HistoryError> {
+        let entries = self.entries();
+        let unsaved = self.unsaved.load(Ordering::Relaxed).min(entries.len());
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| {
+                let lines: Vec<String> = entries[unsaved..]
+                    .iter()
+                    .map(String::as_str)
+                    .map(Self::escape)
+                    .collect();
+                file.write_all(Self::join(&lines).as_bytes())
+            })
+            .map_err(|err| Self::io_error(path, err))?;
+        self.unsaved.store(entries.len(), Ordering::Relaxed);
+
+        self.truncate_file(path)
+    }
+
+    fn truncate(&self, entries: &mut Vec<String>) {
+        let excess = entries.len().saturating_sub(self.size);
+        if excess > 0 {
+            entries.drain(..excess);
+            let unsaved = self.unsaved.load(Ordering::Relaxed);
+            self.unsaved
+                .store(unsaved.saturating_sub(excess), Ordering::Relaxed);
+        }
+    }
+
+    fn truncate_file(&self, path: &Path) -> Result<(), HistoryError> {
+        let content = std::fs::read_to_string(path).map_err(|err| Self::io_error(path, err))?;
+        let lines: Vec<&str> = content.lines().collect();
+        if lines.len() <= self.file_size {
+            return Ok(());
+        }
+
+        let kept = &lines[lines.len() - self.file_size..];
+        std::fs::write(path, Self::join(kept)).map_err(|err| Self::io_error(path, err))
+    }
+
+    fn join(lines: &[impl AsRef<str>]) -> String {
+        lines
+            .iter()
+            .map(|line| format!("{}\n", line.as_ref()))
+            .collect()
+    }
+
+    /// Keeps a multi-line entry on a single line of the file, as `\n`, with
+    /// backslashes doubled.
+    fn escape(entry: &str) -> String {
+        entry.replace('\\', "\\\\").replace('\n', "\\n")
+    }
+
+    fn unescape(line: &str) -> String {
+        let mut entry = String::with_capacity(line.len());
+        let mut chars = line.chars();
+        while let Some(char) = chars.next() {
+            match (char, chars.clone().next()) {
+                ('\\', Some('\\')) => {
+                    chars.next();
+                    entry.push('\\');
+                }
+                ('\\', Some('n')) => {
+                    chars.next();
+                    entry.push('\n');
+                }
+                _ => entry.push(char),
+            }
+        }
+        entry
+    }
+
+    fn io_error(path: &Path, err: std::io::Error) -> HistoryError {
+        HistoryError::Io(format!("{}: {err}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history_file(content: &str) -> tempfile::NamedTempFile {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), content).unwrap();
+        file
+    }
+
+    #[test]
+    fn add_skips_blank_lines() {
+        let history = History::default();
+        assert!(history.add("echo one\n"));
+        assert!(!history.add("   "));
+        assert_eq!(history.entries(), vec!["echo one".to_string()]);
+    }
+
+    #[test]
+    fn histcontrol_ignores_dups_and_space() {
+        let history = History::default().with_control("ignoredups:ignorespace");
+        assert!(history.add("pwd"));
+        assert!(!history.add("pwd"));
+        assert!(!history.add(" secret"));
+        assert!(history.add("ls"));
+        assert!(history.add("pwd"));
+        assert_eq!(history.len(), 3);
+    }
+
+    #[test]
+    fn size_keeps_most_recent_entries() {
+        let history = History::new(None, 2, 2);
+        for line in ["a", "b", "c"] {
+            history.add(line);
+        }
+        assert_eq!(history.entries(), vec!["b".to_string(), "c".to_string()]);
+    }
+
+    #[test]
+    fn delete_entry() {
+        let history = History::default();
+        history.add("a");
+        history.add("b");
+        assert_eq!(history.delete(0), Some("a".to_string()));
+        assert_eq!(history.delete(5), None);
+        assert_eq!(history.entries(), vec!["b".to_string()]);
+    }
+
+    #[test]
+    fn append_only_writes_new_entries() {
+        let file = history_file("old\n");
+        let history = History::new(Some(file.path().to_path_buf()), 10, 10);
+        history.load().unwrap();
+        history.add("new");
+
+        history.save().unwrap();
+        history.save().unwrap();
+        assert_eq!(std::fs::read_to_string(file.path()).unwrap(), "old\nnew\n");
+    }
+
+    #[test]
+    fn file_size_truncates_history_file() {
+        let file = history_file("a\nb\n");
+        let history = History::new(Some(file.path().to_path_buf()), 10, 2);
+        history.add("c");
+
+        history.save().unwrap();
+        assert_eq!(std::fs::read_to_string(file.path()).unwrap(), "b\nc\n");
+
+        history.write_file(file.path()).unwrap();
+        assert_eq!(std::fs::read_to_string(file.path()).unwrap(), "c\n");
+    }
+
+    #[test]
+    fn read_file_keeps_session_entries_unsaved() {
+        let file = history_file("");
+        let other = history_file("old\n");
+        let history = History::new(Some(file.path().to_path_buf()), 10, 10);
+        history.add("new");
+
+        history.read_file(other.path()).unwrap();
+        assert_eq!(
+            history.entries(),
+            vec!["old".to_string(), "new".to_string()]
+        );
+
+        history.save().unwrap();
+        assert_eq!(std::fs::read_to_string(file.path()).unwrap(), "new\n");
+    }
+
+    #[test]
+    fn multi_line_entries_round_trip() {
+        let file = history_file("");
+        let history = History::new(Some(file.path().to_path_buf()), 10, 10);
+        history.add("for i in 1 2\ndo echo \\n\ndone");
+        history.add("echo a\\\\b");
+        history.save().unwrap();
+
+        let loaded = History::new(Some(file.path().to_path_buf()), 10, 10);
+        loaded.load().unwrap();
+        assert_eq!(loaded.entries(), history.entries());
+    }
+}
@@ -0,0 +1,360 @@
+use crate::{
+    exceptions::commands::ShellError,
+    shell::input::commons::{BACK_SLASH, DOUBLE_QUOTE, SINGLE_QUOTE},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListOperator {
+    Sequence,
+    And,
+    Or,
+}
+
+impl ListOperator {
+    fn token(&self) -> &'static str {
+        match self {
+            ListOperator::Sequence => ";",
+            ListOperator::And => "&&",
+            ListOperator::Or => "||",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ListItem {
+    command: String,
+    operator: ListOperator,
+    line: usize,
+}
+
+impl ListItem {
+    pub fn new(command: &str, operator: ListOperator, line: usize) -> Self {
+        Self {
+            command: command.to_owned(),
+            operator,
+            line,
+        }
+    }
+
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// Operator linking this command to the next one in the list.
+    pub fn operator(&self) -> ListOperator {
+        self.operator
+    }
+
+    /// 1-based line on which the command starts.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+#[derive(Debug)]
+struct ListState {
+    current: String,
+    line: usize,
+    start_line: usize,
+    items: Vec<ListItem>,
+    quote: Option<char>,
+    escape_next: bool,
+    in_comment: bool,
+    after_pipe: bool,
+}
+
+impl Default for ListState {
+    fn default() -> Self {
+        Self {
+            current: String::new(),
+            line: 1,
+            start_line: 1,
+            items: Vec::new(),
+            quote: None,
+            escape_next: false,
+            in_comment: false,
+            after_pipe: false,
+        }
+    }
+}
+
+impl ListState {
+    fn push(&mut self, char: char) {
+        if self.current.trim().is_empty() && !char.is_whitespace() {
+            self.start_line = self.line;
+        }
+        if !char.is_whitespace() {
+            self.after_pipe = false;
+        }
+        self.current.push(char);
+    }
+
+    fn at_word_start(&self) -> bool {
+        self.current
+            .chars()
+            .last()
+            .map_or(true, char::is_whitespace)
+    }
+
+    fn finalize_command(&mut self, operator: ListOperator) -> Result<(), ShellError> {
+        let command = self.current.trim();
+
+        if command.is_empty() {
+            if operator != ListOperator::Sequence {
+                return Err(ShellError::SyntaxError(operator.token().to_owned()));
+            }
+            self.current.clear();
+            return Ok(());
+        }
+
+        self.items
+            .push(ListItem::new(command, operator, self.start_line));
+        self.current.clear();
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CommandList(Vec<ListItem>);
+
+impl CommandList {
+    pub fn parse(input: &str) -> Result<Self, ShellError> {
+        let mut state = ListState::default();
+        let mut chars = input.chars().peekable();
+
+        while let Some(char) = chars.next() {
+            if char == '\n' {
+                state.line += 1;
+            }
+
+            if state.in_comment {
+                if char == '\n' {
+                    state.in_comment = false;
+                    state.finalize_command(ListOperator::Sequence)?;
+                }
+                continue;
+            }
+
+            if state.escape_next {
+                state.escape_next = false;
+                if char == '\n' {
+                    state.current.pop();
+                } else {
+                    state.push(char);
+                }
+                continue;
+            }
+
+            if char == BACK_SLASH && state.quote != Some(SINGLE_QUOTE) {
+                state.escape_next = true;
+                state.push(char);
+                continue;
+            }
+
+            if char == SINGLE_QUOTE || char == DOUBLE_QUOTE {
+                match state.quote {
+                    Some(quote) if quote == char => state.quote = None,
+                    None => state.quote = Some(char),
+                    Some(_) => {}
+                }
+                state.push(char);
+                continue;
+            }
+
+            if state.quote.is_some() {
+                state.push(char);
+                continue;
+            }
+
+            match char {
+                '#' if state.at_word_start() => state.in_comment = true,
+                // A pipe carries the command over to the next line
+                '\n' if state.after_pipe => state.push(' '),
+                ';' | '\n' => state.finalize_command(ListOperator::Sequence)?,
+                '&' if chars.peek() == Some(&'&') => {
+                    chars.next();
+                    state.finalize_command(ListOperator::And)?;
+                }
+                '|' if chars.peek() == Some(&'|') => {
+                    chars.next();
+                    state.finalize_command(ListOperator::Or)?;
+                }
+                '|' => {
+                    state.push(char);
+                    state.after_pipe = true;
+                }
+                _ => state.push(char),
+            }
+        }
+
+        if state.quote.is_some() || state.escape_next || state.after_pipe {
+            return Err(ShellError::IncompleteInput);
+        }
+
+        state.finalize_command(ListOperator::Sequence)?;
+
+        if let Some(last) = state.items.last() {
+            if last.operator != ListOperator::Sequence {
+                return Err(ShellError::IncompleteInput);
+            }
+        }
+
+        Ok(Self(state.items))
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, ListItem> {
+        self.0.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commands(input: &str) -> Vec<(String, ListOperator)> {
+        CommandList::parse(input)
+            .unwrap()
+            .iter()
+            .map(|item| (item.command().to_owned(), item.operator()))
+            .collect()
+    }
+
+    #[test]
+    fn single_command() {
+        assert_eq!(
+            commands("echo hello"),
+            vec![("echo hello".to_owned(), ListOperator::Sequence)]
+        );
+    }
+
+    #[test]
+    fn semicolon_separates_commands() {
+        assert_eq!(
+            commands("echo a; echo b"),
+            vec![
+                ("echo a".to_owned(), ListOperator::Sequence),
+                ("echo b".to_owned(), ListOperator::Sequence),
+            ]
+        );
+    }
+
+    #[test]
+    fn newline_separates_commands() {
+        assert_eq!(
+            commands("echo a\n\necho b\n"),
+            vec![
+                ("echo a".to_owned(), ListOperator::Sequence),
+                ("echo b".to_owned(), ListOperator::Sequence),
+            ]
+        );
+    }
+
+    #[test]
+    fn and_or_operators() {
+        assert_eq!(
+            commands("true && echo a || echo b"),
+            vec![
+                ("true".to_owned(), ListOperator::And),
+                ("echo a".to_owned(), ListOperator::Or),
+                ("echo b".to_owned(), ListOperator::Sequence),
+            ]
+        );
+    }
+
+    #[test]
+    fn operators_inside_quotes_are_literal() {
+        assert_eq!(
+            commands("echo 'a; b' \"c && d\""),
+            vec![("echo 'a; b' \"c && d\"".to_owned(), ListOperator::Sequence)]
+        );
+    }
+
+    #[test]
+    fn escaped_semicolon_is_literal() {
+        assert_eq!(
+            commands("echo a\\; b"),
+            vec![("echo a\\; b".to_owned(), ListOperator::Sequence)]
+        );
+    }
+
+    #[test]
+    fn comments_are_skipped() {
+        assert_eq!(
+            commands("# comment\necho a # trailing\necho b#c"),
+            vec![
+                ("echo a".to_owned(), ListOperator::Sequence),
+                ("echo b#c".to_owned(), ListOperator::Sequence),
+            ]
+        );
+    }
+
+    #[test]
+    fn backslash_newline_joins_lines() {
+        assert_eq!(
+            commands("echo a \\\nb"),
+            vec![("echo a b".to_owned(), ListOperator::Sequence)]
+        );
+    }
+
+    #[test]
+    fn commands_record_their_line() {
+        let lines: Vec<usize> =
+            CommandList::parse("echo a\n# comment\n\necho b; echo c\necho 'd\ne'\necho f")
+                .unwrap()
+                .iter()
+                .map(ListItem::line)
+                .collect();
+        assert_eq!(lines, vec![1, 4, 4, 5, 7]);
+    }
+
+    #[test]
+    fn empty_input_is_empty_list() {
+        assert!(CommandList::parse("   ").unwrap().is_empty());
+        assert!(CommandList::parse(";;").unwrap().is_empty());
+    }
+
+    #[test]
+    fn leading_operator_is_syntax_error() {
+        assert_eq!(
+            CommandList::parse("&& echo a").unwrap_err(),
+            ShellError::SyntaxError("&&".to_owned())
+        );
+    }
+
+    #[test]
+    fn unfinished_input_is_incomplete() {
+        for input in [
+            "echo a ||",
+            "true &&\n",
+            "echo 'a\nb",
+            "echo \"a",
+            "echo a \\",
+            "echo a |",
+        ] {
+            assert_eq!(
+                CommandList::parse(input).unwrap_err(),
+                ShellError::IncompleteInput,
+                "{input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn operators_continue_on_next_line() {
+        assert_eq!(
+            commands("true &&\necho a"),
+            vec![
+                ("true".to_owned(), ListOperator::And),
+                ("echo a".to_owned(), ListOperator::Sequence),
+            ]
+        );
+        assert_eq!(
+            commands("echo a |\n  wc"),
+            vec![("echo a |   wc".to_owned(), ListOperator::Sequence)]
+        );
+    }
+}
@@ -9,6 +9,12 @@ pub const CRLF: &str = "\r\n";
 pub const BACK_SPACE: u8 = 127;
 pub const CTRL_C: u8 = 3;
 pub const CTRL_H: u8 = 8;
+pub const ESCAPE: u8 = 27;
 pub const BELL_CHAR: &str = "\x07";
+pub const REVERSE_VIDEO: &str = "\x1b[7m";
+pub const NORMAL_VIDEO: &str = "\x1b[27m";
 pub const ASCII_SPACE: u8 = 32;
 pub const ASCII_DEL: u8 = 127;
+/// Start and end of a prompt part that takes no room on screen.
+pub const IGNORE_START: char = '\x01';
+pub const IGNORE_END: char = '\x02';
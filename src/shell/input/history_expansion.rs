@@ -0,0 +1,464 @@
+use std::sync::Arc;
+
+use crate::{
+    exceptions::history_error::HistoryError,
+    shell::{
+        history::History,
+        input::commons::{BACK_SLASH, DOUBLE_QUOTE, SINGLE_QUOTE},
+    },
+};
+
+const HISTORY_CHAR: char = '!';
+const QUICK_SUBSTITUTION_CHAR: char = '^';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WordBound {
+    Index(usize),
+    Last,
+    LastButOne,
+}
+
+/// csh-style history expansion (`!!`, `!$`, `!n`, `!prefix`, `^old^new`).
+pub struct HistoryExpansion {
+    history: Arc<History>,
+}
+
+impl HistoryExpansion {
+    pub fn new(history: Arc<History>) -> Self {
+        Self { history }
+    }
+
+    /// Returns the expanded line, or `None` when nothing was expanded.
+    pub fn expand(&self, line: &str) -> Result<Option<String>, HistoryError> {
+        if let Some(rest) = line.strip_prefix(QUICK_SUBSTITUTION_CHAR) {
+            return self.quick_substitution(rest).map(Some);
+        }
+
+        let chars: Vec<char> = line.chars().collect();
+        let mut output = String::new();
+        let mut expanded = false;
+        let mut in_single_quote = false;
+        let mut in_double_quote = false;
+        let mut idx = 0;
+
+        while idx < chars.len() {
+            let char = chars[idx];
+            match char {
+                BACK_SLASH if !in_single_quote => {
+                    output.extend(&chars[idx..(idx + 2).min(chars.len())]);
+                    idx += 2;
+                    continue;
+                }
+                SINGLE_QUOTE if !in_double_quote => in_single_quote = !in_single_quote,
+                DOUBLE_QUOTE if !in_single_quote => in_double_quote = !in_double_quote,
+                HISTORY_CHAR
+                    if !in_single_quote
+                        && Self::starts_event(chars.get(idx + 1), in_double_quote) =>
+                {
+                    let (text, consumed) = self.expand_reference(&chars[idx + 1..])?;
+                    output.push_str(&text);
+                    expanded = true;
+                    idx += consumed + 1;
+                    continue;
+                }
+                _ => {}
+            }
+            output.push(char);
+            idx += 1;
+        }
+
+        Ok(expanded.then_some(output))
+    }
+
+    fn starts_event(next: Option<&char>, in_double_quote: bool) -> bool {
+        match next {
+            None => false,
+            Some(&char) if char.is_whitespace() || char == '=' || char == '(' => false,
+            Some(&DOUBLE_QUOTE) => !in_double_quote,
+            Some(_) => true,
+        }
+    }
+
+    /// Expands the reference following a `!`, returning the text and the
+    /// number of characters it used.
+    fn expand_reference(&self, chars: &[char]) -> Result<(String, usize), HistoryError> {
+        let mut pos = 0;
+        let event = match chars[0] {
+            HISTORY_CHAR => {
+                pos = 1;
+                self.previous(1)
+            }
+            // Word designators without an event refer to the previous command
+            '$' | '^' | '*' | ':' => self.previous(1),
+            '-' | '0'..='9' => {
+                let negative = chars[0] == '-';
+                pos = usize::from(negative);
+                let digits = Self::count_while(&chars[pos..], |c| c.is_ascii_digit());
+                let number: String = chars[pos..pos + digits].iter().collect();
+                pos += digits;
+                match (number.parse::<usize>(), negative) {
+                    (Ok(offset), true) => self.previous(offset),
+                    (Ok(number), false) => number.checked_sub(1).and_then(|i| self.history.get(i)),
+                    (Err(_), _) => None,
+                }
+            }
+            '?' => {
+                let length = Self::count_while(&chars[1..], |c| c != '?');
+                let needle: String = chars[1..1 + length].iter().collect();
+                pos = (length + 2).min(chars.len());
+                self.find(|entry| entry.contains(&needle))
+            }
+            _ => {
+                pos = Self::count_while(chars, |c| {
+                    !c.is_whitespace() && !matches!(c, ':' | ';' | '&' | '|' | '"' | '\'')
+                });
+                let prefix: String = chars[..pos].iter().collect();
+                self.find(|entry| entry.starts_with(&prefix))
+            }
+        };
+
+        let reference = || format!("{HISTORY_CHAR}{}", chars[..pos].iter().collect::<String>());
+        let event = event.ok_or_else(|| HistoryError::EventNotFound(reference()))?;
+
+        let (mut text, consumed) = self.select_words(&event, &chars[pos..])?;
+        pos += consumed;
+
+        while chars.get(pos) == Some(&':') {
+            match self.apply_modifier(&text, &chars[pos + 1..])? {
+                Some((modified, consumed)) => {
+                    text = modified;
+                    pos += consumed + 1;
+                }
+                None => break,
+            }
+        }
+
+        Ok((text, pos))
+    }
+
+    fn select_words(&self, event: &str, chars: &[char]) -> Result<(String, usize), HistoryError> {
+        let (designator, offset) = match chars.first() {
+            Some('^' | '$' | '*') => (chars, 0),
+            Some(':') if chars.get(1).is_some_and(|&c| Self::is_designator(c)) => (&chars[1..], 1),
+            _ => return Ok((event.to_owned(), 0)),
+        };
+
+        let (start, end, consumed) = Self::parse_designator(designator);
+        let words = Self::split_words(event);
+        let resolve = |bound: WordBound| match bound {
+            WordBound::Index(index) => Some(index),
+            WordBound::Last => words.len().checked_sub(1),
+            WordBound::LastButOne => words.len().checked_sub(2),
+        };
+
+        let spec: String = designator[..consumed].iter().collect();
+        let bad_specifier = || HistoryError::BadWordSpecifier(spec.clone());
+
+        // `*` selects nothing when there are no arguments
+        if designator[0] == '*' && words.len() < 2 {
+            return Ok((String::new(), consumed + offset));
+        }
+
+        let start = resolve(start).ok_or_else(bad_specifier)?;
+        let end = resolve(end).ok_or_else(bad_specifier)?;
+        if start > end || end >= words.len() {
+            return Err(bad_specifier());
+        }
+
+        Ok((words[start..=end].join(" "), consumed + offset))
+    }
+
+    fn is_designator(char: char) -> bool {
+        char.is_ascii_digit() || matches!(char, '^' | '$' | '*' | '-')
+    }
+
+    fn parse_designator(chars: &[char]) -> (WordBound, WordBound, usize) {
+        let number = |chars: &[char]| -> Option<(usize, usize)> {
+            let digits = Self::count_while(chars, |c| c.is_ascii_digit());
+            let value = chars[..digits].iter().collect::<String>().parse().ok()?;
+            Some((value, digits))
+        };
+
+        let (start, mut consumed) = match chars[0] {
+            '^' => (WordBound::Index(1), 1),
+            '$' => return (WordBound::Last, WordBound::Last, 1),
+            '*' => return (WordBound::Index(1), WordBound::Last, 1),
+            '-' => (WordBound::Index(0), 0),
+            _ => match number(chars) {
+                Some((value, digits)) => (WordBound::Index(value), digits),
+                None => return (WordBound::Index(0), WordBound::Index(0), 0),
+            },
+        };
+
+        let end = match chars.get(consumed) {
+            Some('*') => {
+                consumed += 1;
+                WordBound::Last
+            }
+            Some('-') => {
+                consumed += 1;
+                match chars.get(consumed) {
+                    Some('$') => {
+                        consumed += 1;
+                        WordBound::Last
+                    }
+                    _ => match number(&chars[consumed..]) {
+                        Some((value, digits)) => {
+                            consumed += digits;
+                            WordBound::Index(value)
+                        }
+                        None => WordBound::LastButOne,
+                    },
+                }
+            }
+            _ => start,
+        };
+
+        (start, end, consumed)
+    }
+
+    /// Applies the modifier after a `:`, or returns `None` if there is none.
+    fn apply_modifier(
+        &self,
+        text: &str,
+        chars: &[char],
+    ) -> Result<Option<(String, usize)>, HistoryError> {
+        let modified = match chars.first() {
+            Some('h') => match text.rfind('/') {
+                Some(0) => "/".to_owned(),
+                Some(idx) => text[..idx].to_owned(),
+                None => text.to_owned(),
+            },
+            Some('t') => text.rsplit('/').next().unwrap_or(text).to_owned(),
+            Some('r') => {
+                let name_start = text.rfind('/').map_or(0, |idx| idx + 1);
+                match text[name_start..].rfind('.') {
+                    Some(dot) if dot > 0 => text[..name_start + dot].to_owned(),
+                    _ => text.to_owned(),
+                }
+            }
+            Some('s') => return self.substitute(text, &chars[1..], false).map(Some),
+            Some('g') if chars.get(1) == Some(&'s') => {
+                return self
+                    .substitute(text, &chars[2..], true)
+                    .map(|(text, consumed)| Some((text, consumed + 1)));
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some((modified, 1)))
+    }
+
+    /// Handles `s/old/new/`, where any character may act as the delimiter.
+    fn substitute(
+        &self,
+        text: &str,
+        chars: &[char],
+        global: bool,
+    ) -> Result<(String, usize), HistoryError> {
+        let Some(&delimiter) = chars.first() else {
+            return Err(HistoryError::SubstitutionFailed(text.to_owned()));
+        };
+
+        let mut parts = vec![String::new()];
+        let mut consumed = 1;
+        while let Some(&char) = chars.get(consumed) {
+            consumed += 1;
+            match char {
+                BACK_SLASH if chars.get(consumed) == Some(&delimiter) => {
+                    parts.last_mut().unwrap().push(delimiter);
+                    consumed += 1;
+                }
+                char if char == delimiter => {
+                    if parts.len() == 2 {
+                        break;
+                    }
+                    parts.push(String::new());
+                }
+                char => parts.last_mut().unwrap().push(char),
+            }
+        }
+
+        let old = &parts[0];
+        let new = parts
+            .get(1)
+            .map_or(String::new(), |new| new.replace('&', old));
+        if old.is_empty() || !text.contains(old.as_str()) {
+            return Err(HistoryError::SubstitutionFailed(format!(
+                ":s{delimiter}{old}"
+            )));
+        }
+
+        let replaced = match global {
+            true => text.replace(old.as_str(), &new),
+            false => text.replacen(old.as_str(), &new, 1),
+        };
+        Ok((replaced, consumed + 1))
+    }
+
+    fn quick_substitution(&self, rest: &str) -> Result<String, HistoryError> {
+        let mut parts = rest.splitn(3, QUICK_SUBSTITUTION_CHAR);
+        let old = parts.next().unwrap_or_default();
+        let new = parts.next().unwrap_or_default();
+        let tail = parts.next().unwrap_or_default();
+
+        let event = self
+            .previous(1)
+            .ok_or_else(|| HistoryError::EventNotFound(format!("^{rest}")))?;
+        if old.is_empty() || !event.contains(old) {
+            return Err(HistoryError::SubstitutionFailed(format!("^{old}")));
+        }
+
+        Ok(format!("{}{tail}", event.replacen(old, new, 1)))
+    }
+
+    fn previous(&self, offset: usize) -> Option<String> {
+        let index = self.history.len().checked_sub(offset)?;
+        self.history.get(index)
+    }
+
+    fn find(&self, predicate: impl Fn(&str) -> bool) -> Option<String> {
+        self.history
+            .entries()
+            .into_iter()
+            .rev()
+            .find(|entry| predicate(entry))
+    }
+
+    fn count_while(chars: &[char], predicate: impl Fn(char) -> bool) -> usize {
+        chars.iter().take_while(|&&char| predicate(char)).count()
+    }
+
+    /// Splits on blanks while keeping quoted text inside a single word.
+    fn split_words(line: &str) -> Vec<String> {
+        let mut words = Vec::new();
+        let mut current = String::new();
+        let mut quote: Option<char> = None;
+
+        for char in line.chars() {
+            match (char, quote) {
+                (SINGLE_QUOTE | DOUBLE_QUOTE, None) => quote = Some(char),
+                (char, Some(open)) if char == open => quote = None,
+                (char, None) if char.is_whitespace() => {
+                    if !current.is_empty() {
+                        words.push(std::mem::take(&mut current));
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            current.push(char);
+        }
+
+        if !current.is_empty() {
+            words.push(current);
+        }
+        words
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expansion(lines: &[&str]) -> HistoryExpansion {
+        let history = Arc::new(History::default());
+        for line in lines {
+            history.add(line);
+        }
+        HistoryExpansion::new(history)
+    }
+
+    #[test]
+    fn lines_without_references_are_untouched() {
+        let expansion = expansion(&["echo hi"]);
+        assert_eq!(expansion.expand("echo hi!"), Ok(None));
+        assert_eq!(expansion.expand("echo ! x"), Ok(None));
+        assert_eq!(expansion.expand("echo '!!'"), Ok(None));
+        assert_eq!(expansion.expand("echo \\!!"), Ok(None));
+    }
+
+    #[test]
+    fn event_designators() {
+        let expansion = expansion(&["ls /tmp", "cat notes.txt", "echo one two"]);
+        assert_eq!(
+            expansion.expand("sudo !!"),
+            Ok(Some("sudo echo one two".to_string()))
+        );
+        assert_eq!(expansion.expand("!1"), Ok(Some("ls /tmp".to_string())));
+        assert_eq!(
+            expansion.expand("!-2"),
+            Ok(Some("cat notes.txt".to_string()))
+        );
+        assert_eq!(
+            expansion.expand("!ca"),
+            Ok(Some("cat notes.txt".to_string()))
+        );
+        assert_eq!(expansion.expand("!?tmp?"), Ok(Some("ls /tmp".to_string())));
+        assert_eq!(
+            expansion.expand("!nope"),
+            Err(HistoryError::EventNotFound("!nope".to_string()))
+        );
+    }
+
+    #[test]
+    fn word_designators() {
+        let expansion = expansion(&["cp a.txt 'b c' dir/"]);
+        assert_eq!(expansion.expand("vim !$"), Ok(Some("vim dir/".to_string())));
+        assert_eq!(expansion.expand("!^"), Ok(Some("a.txt".to_string())));
+        assert_eq!(
+            expansion.expand("echo !*"),
+            Ok(Some("echo a.txt 'b c' dir/".to_string()))
+        );
+        assert_eq!(expansion.expand("!!:0"), Ok(Some("cp".to_string())));
+        assert_eq!(
+            expansion.expand("!!:1-2"),
+            Ok(Some("a.txt 'b c'".to_string()))
+        );
+        assert_eq!(expansion.expand("!!:2-"), Ok(Some("'b c'".to_string())));
+        assert_eq!(
+            expansion.expand("!!:7"),
+            Err(HistoryError::BadWordSpecifier("7".to_string()))
+        );
+    }
+
+    #[test]
+    fn modifiers() {
+        let expansion = expansion(&["vim /src/shell/main.rs"]);
+        assert_eq!(
+            expansion.expand("cd !$:h"),
+            Ok(Some("cd /src/shell".to_string()))
+        );
+        assert_eq!(expansion.expand("!$:t"), Ok(Some("main.rs".to_string())));
+        assert_eq!(expansion.expand("!$:t:r"), Ok(Some("main".to_string())));
+        assert_eq!(
+            expansion.expand("!!:s/main/lib/"),
+            Ok(Some("vim /src/shell/lib.rs".to_string()))
+        );
+        assert_eq!(
+            expansion.expand("!!:gs/s/S/"),
+            Ok(Some("vim /Src/Shell/main.rS".to_string()))
+        );
+    }
+
+    #[test]
+    fn quick_substitution() {
+        let expansion = expansion(&["echo foo foo"]);
+        assert_eq!(
+            expansion.expand("^foo^bar"),
+            Ok(Some("echo bar foo".to_string()))
+        );
+        assert_eq!(
+            expansion.expand("^baz^bar"),
+            Err(HistoryError::SubstitutionFailed("^baz".to_string()))
+        );
+    }
+
+    #[test]
+    fn double_quotes_allow_expansion() {
+        let expansion = expansion(&["echo hi"]);
+        assert_eq!(
+            expansion.expand("echo \"!!\""),
+            Ok(Some("echo \"echo hi\"".to_string()))
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use crate::shell::history::History;
+
+/// Walks the history for a single line being edited. The line typed before
+/// browsing is kept as a draft and comes back past the newest entry.
+pub struct HistoryNavigator {
+    history: Arc<History>,
+    position: Option<usize>,
+    draft: String,
+}
+
+impl HistoryNavigator {
+    pub fn new(history: Arc<History>) -> Self {
+        Self {
+            history,
+            position: None,
+            draft: String::new(),
+        }
+    }
+
+    /// Moves to an older entry. With `prefix_search` only entries starting
+    /// with the draft are considered.
+    pub fn previous(&mut self, line: &str, prefix_search: bool) -> Option<String> {
+        if self.position.is_none() {
+            self.draft = line.to_owned();
+        }
+
+        let start = self.position.unwrap_or(self.history.len());
+        let found = (0..start)
+            .rev()
+            .find(|&index| self.matches(index, prefix_search))?;
+
+        self.position = Some(found);
+        self.history.get(found)
+    }
+
+    /// Moves to a newer entry, returning the draft after the newest one.
+    pub fn next(&mut self, prefix_search: bool) -> Option<String> {
+        let current = self.position?;
+        let found =
+            (current + 1..self.history.len()).find(|&index| self.matches(index, prefix_search));
+
+        self.position = found;
+        match found {
+            Some(index) => self.history.get(index),
+            None => Some(self.draft.clone()),
+        }
+    }
+
+    fn matches(&self, index: usize, prefix_search: bool) -> bool {
+        !prefix_search
+            || self
+                .history
+                .get(index)
+                .is_some_and(|entry| entry.starts_with(&self.draft))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn navigator() -> HistoryNavigator {
+        let history = Arc::new(History::default());
+        for line in ["echo one", "pwd", "echo two"] {
+            history.add(line);
+        }
+        HistoryNavigator::new(history)
+    }
+
+    #[test]
+    fn walk_back_and_forward() {
+        let mut navigator = navigator();
+        assert_eq!(navigator.previous("", false), Some("echo two".to_string()));
+        assert_eq!(
+            navigator.previous("echo two", false),
+            Some("pwd".to_string())
+        );
+        assert_eq!(
+            navigator.previous("pwd", false),
+            Some("echo one".to_string())
+        );
+        assert_eq!(navigator.previous("echo one", false), None);
+        assert_eq!(navigator.next(false), Some("pwd".to_string()));
+    }
+
+    #[test]
+    fn draft_comes_back_past_newest_entry() {
+        let mut navigator = navigator();
+        assert_eq!(navigator.next(false), None);
+        navigator.previous("ec", false);
+        assert_eq!(navigator.next(false), Some("ec".to_string()));
+        assert_eq!(navigator.next(false), None);
+    }
+
+    #[test]
+    fn prefix_search_skips_other_entries() {
+        let mut navigator = navigator();
+        assert_eq!(
+            navigator.previous("echo", true),
+            Some("echo two".to_string())
+        );
+        assert_eq!(
+            navigator.previous("echo two", true),
+            Some("echo one".to_string())
+        );
+        assert_eq!(navigator.next(true), Some("echo two".to_string()));
+        assert_eq!(navigator.next(true), Some("echo".to_string()));
+    }
+}
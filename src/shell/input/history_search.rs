@@ -0,0 +1,151 @@
+use std::sync::Arc;
+
+use crate::shell::history::History;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchDirection {
+    Backward,
+    Forward,
+}
+
+/// Incremental search state behind Ctrl-R and Ctrl-S.
+pub struct HistorySearch {
+    history: Arc<History>,
+    direction: SearchDirection,
+    query: String,
+    position: Option<usize>,
+    failed: bool,
+}
+
+impl HistorySearch {
+    pub fn new(history: Arc<History>, direction: SearchDirection) -> Self {
+        Self {
+            history,
+            direction,
+            query: String::new(),
+            position: None,
+            failed: false,
+        }
+    }
+
+    pub fn matched(&self) -> Option<String> {
+        self.position.and_then(|index| self.history.get(index))
+    }
+
+    pub fn push(&mut self, char: char) {
+        self.query.push(char);
+        // The current match stays selected while it still matches
+        let start = match self.direction {
+            SearchDirection::Backward => self.position.map_or(self.history.len(), |p| p + 1),
+            SearchDirection::Forward => self.position.unwrap_or(0),
+        };
+        self.search_from(start);
+    }
+
+    pub fn pop(&mut self) {
+        self.query.pop();
+        self.position = None;
+        let start = match self.direction {
+            SearchDirection::Backward => self.history.len(),
+            SearchDirection::Forward => 0,
+        };
+        self.search_from(start);
+    }
+
+    /// Moves to the next match in `direction`, skipping the current one.
+    pub fn search_again(&mut self, direction: SearchDirection) {
+        self.direction = direction;
+        let start = match direction {
+            SearchDirection::Backward => self.position.unwrap_or(self.history.len()),
+            SearchDirection::Forward => self.position.map_or(0, |p| p + 1),
+        };
+        self.search_from(start);
+    }
+
+    pub fn display(&self) -> String {
+        let label = match self.direction {
+            SearchDirection::Backward => "reverse-i-search",
+            SearchDirection::Forward => "i-search",
+        };
+        let failed = if self.failed { "failed " } else { "" };
+        let matched = self.matched().unwrap_or_default();
+        format!("({failed}{label})'{}': {matched}", self.query)
+    }
+
+    /// Backward searches look below `start`, forward ones from `start` on.
+    fn search_from(&mut self, start: usize) {
+        let len = self.history.len();
+        let found = match self.direction {
+            SearchDirection::Backward => (0..start.min(len)).rev().find(|&i| self.is_match(i)),
+            SearchDirection::Forward => (start..len).find(|&i| self.is_match(i)),
+        };
+
+        self.failed = found.is_none();
+        if found.is_some() {
+            self.position = found;
+        }
+    }
+
+    fn is_match(&self, index: usize) -> bool {
+        self.history
+            .get(index)
+            .is_some_and(|entry| entry.contains(&self.query))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search(direction: SearchDirection) -> HistorySearch {
+        let history = Arc::new(History::default());
+        for line in ["echo one", "pwd", "echo two", "cd /tmp"] {
+            history.add(line);
+        }
+        HistorySearch::new(history, direction)
+    }
+
+    #[test]
+    fn reverse_search_updates_while_typing() {
+        let mut search = search(SearchDirection::Backward);
+        search.push('e');
+        assert_eq!(search.matched(), Some("echo two".to_string()));
+        search.push('c');
+        search.push('h');
+        search.push('o');
+        search.push(' ');
+        search.push('o');
+        assert_eq!(search.matched(), Some("echo one".to_string()));
+        assert_eq!(search.display(), "(reverse-i-search)'echo o': echo one");
+    }
+
+    #[test]
+    fn repeated_search_cycles_matches() {
+        let mut search = search(SearchDirection::Backward);
+        search.push('o');
+        assert_eq!(search.matched(), Some("echo two".to_string()));
+        search.search_again(SearchDirection::Backward);
+        assert_eq!(search.matched(), Some("echo one".to_string()));
+        search.search_again(SearchDirection::Forward);
+        assert_eq!(search.matched(), Some("echo two".to_string()));
+    }
+
+    #[test]
+    fn failed_search_keeps_last_match() {
+        let mut search = search(SearchDirection::Backward);
+        search.push('p');
+        search.push('x');
+        assert_eq!(search.matched(), Some("cd /tmp".to_string()));
+        assert_eq!(search.display(), "(failed reverse-i-search)'px': cd /tmp");
+
+        search.pop();
+        assert_eq!(search.display(), "(reverse-i-search)'p': cd /tmp");
+    }
+
+    #[test]
+    fn forward_search_starts_at_oldest_entry() {
+        let mut search = search(SearchDirection::Forward);
+        search.push('e');
+        assert_eq!(search.display(), "(i-search)'e': echo one");
+    }
+}
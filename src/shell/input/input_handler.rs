@@ -1,17 +1,41 @@
-use std::io::{self, Read, Stdin, StdinLock, StdoutLock, Write};
+use std::{
+    cell::RefCell,
+    io::{self, Write},
+    ops::Range,
+    sync::Arc,
+};
 
 use crate::{
     exceptions::commands::ShellError,
+    port::command::CommandResult,
     shell::{
-        completion::{self, builtins::BuiltinsCompletion, CompletionComponent},
-        input::commons::{
-            ASCII_DEL, ASCII_SPACE, BACK_SPACE, BELL_CHAR, CARRIAGE, CRLF, CTRL_C, CTRL_H,
-            LINEBREAK, TABULATION,
+        completion::{
+            builtins::BuiltinsCompletion, context::CompletionContext, matching::Matching,
+            menu::CompletionMenu, Completed, CompletionComponent,
+        },
+        file::FileManager,
+        history::History,
+        input::{
+            commons::{BELL_CHAR, CRLF},
+            history_navigator::HistoryNavigator,
+            history_search::{HistorySearch, SearchDirection},
+            key::{Key, KeyInput, KeyReader, RawStdin},
+            kill_ring::{KillDirection, KillRing},
+            line_buffer::LineBuffer,
+            render::{Renderer, Repaint},
+            vi::{ViAction, ViEditor, ViMode},
         },
+        options::{ShellOption, ShellOptions},
+        output_handler::OutputHandler,
         raw_mode::RawMode,
+        terminal,
     },
 };
 
+/// Past this many matches, the user is asked before they are listed.
+const QUERY_ITEMS: usize = 100;
+const MORE_PROMPT: &str = "--More--";
+
 // struct InputState<'a> {
 //     buffer: String,
 //     stdin: StdinLock<'a>,
@@ -20,86 +44,631 @@ use crate::{
 // }
 pub enum InputResult {
     Input(String),
-    // Completion(String),
-    MultiCompletion {
-        completion_items: String,
-        input: String,
-    },
     Reset,
+    Eof,
+}
+
+/// What the previous key did, so kills can accumulate and Alt-Y can
+/// replace the text that was just yanked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LastEdit {
+    Kill,
+    Yank(Range<usize>),
+    Other,
+}
+
+/// A menu cycled through with Tab and Shift-Tab, the selected match
+/// replaces the word.
+struct MenuState {
+    menu: CompletionMenu,
+    start: usize,
+    replaced: usize,
+    /// Whether the menu is listed under the line.
+    shown: bool,
 }
 
 pub(crate) struct InputHandler {
     completion: BuiltinsCompletion,
+    history: Arc<History>,
+    /// Kept across lines, it may have read ahead of the line it returned.
+    input: RefCell<KeyReader<RawStdin>>,
+    kill_ring: RefCell<KillRing>,
+    vi: RefCell<ViEditor>,
+    options: Arc<ShellOptions>,
+    output_handler: Arc<OutputHandler>,
+    renderer: Renderer,
 }
 
 impl InputHandler {
-    pub(crate) fn new(completion: BuiltinsCompletion) -> Self {
-        Self { completion }
+    pub(crate) fn new(completion: BuiltinsCompletion, history: Arc<History>) -> Self {
+        Self {
+            completion,
+            history,
+            input: RefCell::new(KeyReader::new(RawStdin)),
+            kill_ring: RefCell::new(KillRing::default()),
+            vi: RefCell::new(ViEditor::default()),
+            options: Arc::new(ShellOptions::default()),
+            output_handler: Arc::new(OutputHandler::new(Arc::new(FileManager))),
+            renderer: Renderer::default(),
+        }
     }
 
-    pub(crate) fn handle(&self, previous_input: Option<String>) -> Result<InputResult, ShellError> {
-        let _raw_mode = RawMode::enable()?;
+    pub(crate) fn renderer(&self) -> &Renderer {
+        &self.renderer
+    }
+
+    pub(crate) fn with_options(mut self, options: Arc<ShellOptions>) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub(crate) fn with_output_handler(mut self, output_handler: Arc<OutputHandler>) -> Self {
+        self.output_handler = output_handler;
+        self
+    }
+
+    fn is_vi(&self) -> bool {
+        self.options.is_enabled(ShellOption::Vi)
+    }
 
-        let mut buffer = previous_input.unwrap_or_default();
-        let mut tmp_buffer = [0u8; 1];
-        let mut stdin = io::stdin().lock();
+    /// The prompt of a new line, which starts in vi insert mode.
+    pub(crate) fn prompt(&self, prompt: &str) -> String {
+        self.mode_prompt(prompt, ViMode::Insert)
+    }
+
+    /// The mode indicator goes on the last row of the prompt.
+    fn mode_prompt(&self, prompt: &str, mode: ViMode) -> String {
+        if !self.is_vi() {
+            return prompt.to_owned();
+        }
+        let (head, last) = prompt.split_at(prompt.rfind('\n').map_or(0, |idx| idx + 1));
+        format!("{head}{}{last}", mode.indicator())
+    }
+
+    pub(crate) fn handle(
+        &self,
+        prompt: &str,
+        previous_input: Option<String>,
+    ) -> Result<InputResult, ShellError> {
+        let raw_mode = RawMode::enable()?;
+
+        let mut buffer = LineBuffer::new(previous_input.unwrap_or_default());
+        let mut stdin = self.input.borrow_mut();
+        let stdin = &mut *stdin;
         let mut stdout = io::stdout().lock();
 
         let mut is_tab_pressed = false;
+        let mut menu: Option<MenuState> = None;
+        let mut navigator = HistoryNavigator::new(Arc::clone(&self.history));
+        let mut pending_key = None;
+        let mut last_edit = LastEdit::Other;
+
+        let base_prompt = prompt;
+        let mut vi = self.vi.borrow_mut();
+        vi.start_line();
+        let mut vi_search = None;
+        let mut prompt = self.mode_prompt(base_prompt, vi.mode());
 
         loop {
-            stdin
-                .read_exact(&mut tmp_buffer[..])
-                .map_err(|e| ShellError::Uncontroled(e.to_string()))?;
-
-            match tmp_buffer[0] {
-                TABULATION => {
-                    let completion = self.completion.execute(&buffer, is_tab_pressed);
-                    match completion {
-                        Some(completion_item) => {
-                            if is_tab_pressed {
-                                return Ok(InputResult::MultiCompletion {
-                                    completion_items: format!("{CRLF}{completion_item}\n"),
-                                    input: buffer,
+            let key = match pending_key.take() {
+                Some(key) => key,
+                None => Key::read(stdin)?,
+            };
+            let previous_edit = std::mem::replace(&mut last_edit, LastEdit::Other);
+            if key != Key::Tab {
+                is_tab_pressed = false;
+            }
+            if !matches!(key, Key::Tab | Key::BackTab) {
+                // The selected match is kept and the menu cleared
+                if menu.take().is_some_and(|state| state.shown) {
+                    self.refresh_line(&mut stdout, &prompt, &buffer)?;
+                }
+            }
+
+            if self.is_vi() {
+                if key == Key::Escape {
+                    vi.escape(&mut buffer);
+                    prompt = self.mode_prompt(base_prompt, vi.mode());
+                    self.refresh_line(&mut stdout, &prompt, &buffer)?;
+                    continue;
+                }
+
+                // Escape typed quickly is read together with the next key
+                let key = match key {
+                    Key::Alt(c) => {
+                        vi.escape(&mut buffer);
+                        Key::Char(c)
+                    }
+                    key => key,
+                };
+
+                if let (Key::Char(c), ViMode::Normal) = (key, vi.mode()) {
+                    let action = vi.normal(&mut buffer, c);
+                    prompt = self.mode_prompt(base_prompt, vi.mode());
+                    match action {
+                        ViAction::Pending => {}
+                        ViAction::Redraw => self.refresh_line(&mut stdout, &prompt, &buffer)?,
+                        ViAction::Bell => self.write_output(&mut stdout, BELL_CHAR.as_bytes())?,
+                        ViAction::HistoryPrevious | ViAction::HistoryNext => {
+                            let entry = match action {
+                                ViAction::HistoryPrevious => {
+                                    navigator.previous(buffer.as_str(), false)
+                                }
+                                _ => navigator.next(false),
+                            };
+                            self.show_entry(&mut stdout, &prompt, &mut buffer, entry)?;
+                        }
+                        ViAction::Search(direction) => {
+                            vi_search = self.vi_search(stdin, &mut stdout, direction)?;
+                            let entry = vi_search.as_ref().and_then(|(search, _)| search.matched());
+                            self.show_entry(&mut stdout, &prompt, &mut buffer, entry)?;
+                        }
+                        ViAction::SearchAgain { reverse } => {
+                            let entry = vi_search.as_mut().and_then(|(search, direction)| {
+                                search.search_again(match (*direction, reverse) {
+                                    (SearchDirection::Backward, false)
+                                    | (SearchDirection::Forward, true) => SearchDirection::Backward,
+                                    _ => SearchDirection::Forward,
                                 });
-                            }
+                                search.matched()
+                            });
+                            self.show_entry(&mut stdout, &prompt, &mut buffer, entry)?;
+                        }
+                        ViAction::Edit => {
+                            drop(raw_mode);
+                            return self.edit_in_editor(&mut stdout, buffer.as_str());
+                        }
+                    }
+                    continue;
+                }
+            }
+
+            match key {
+                Key::Tab | Key::BackTab => {
+                    if let Some(state) = menu.as_mut() {
+                        self.cycle(&mut stdout, &prompt, &mut buffer, state, key == Key::Tab)?;
+                        continue;
+                    }
 
-                            is_tab_pressed = true;
-                            buffer.push_str(&completion_item);
-                            self.write_output(&mut stdout, completion_item.as_bytes())?;
+                    // Shift-Tab goes straight to the menu, from its last match
+                    let context = CompletionContext::parse(buffer.before_cursor())
+                        .with_matching(Matching::from_options(&self.options));
+                    let multiple = is_tab_pressed || key == Key::BackTab;
+                    match self.completion.execute(&context, multiple) {
+                        Some(Completed::Menu(matches)) => {
+                            let word = context.typed().len();
+                            menu = self.show_menu(
+                                stdin,
+                                &mut stdout,
+                                &prompt,
+                                &buffer,
+                                matches,
+                                word,
+                            )?;
+                            if let (Some(state), Key::BackTab) = (menu.as_mut(), key) {
+                                self.cycle(&mut stdout, &prompt, &mut buffer, state, false)?;
+                            }
+                        }
+                        Some(Completed::Insert(completion_item)) => {
+                            buffer.insert_str(&completion_item);
+                            self.echo(&mut stdout, &prompt, &buffer, &completion_item)?;
                         }
-                        None => {
-                            is_tab_pressed = true;
-                            self.write_output(&mut stdout, BELL_CHAR.as_bytes())?;
+                        Some(Completed::Replace(word)) => {
+                            let end = buffer.cursor();
+                            buffer.replace(end - context.typed().len()..end, &word);
+                            self.refresh_line(&mut stdout, &prompt, &buffer)?;
                         }
+                        None => self.write_output(&mut stdout, BELL_CHAR.as_bytes())?,
+                    }
+                    is_tab_pressed = true;
+                }
+                Key::Enter => {
+                    // Output starts below the last row of a wrapped line
+                    if !buffer.is_at_end() {
+                        buffer.move_end();
+                        self.refresh_line(&mut stdout, &prompt, &buffer)?;
                     }
+                    self.write_output(&mut stdout, CRLF.as_bytes())?;
+                    break;
+                }
+                Key::Interrupt => {
+                    return Ok(InputResult::Reset);
                 }
-                CARRIAGE | LINEBREAK => {
+                Key::Ctrl('d') | Key::Eof if buffer.is_empty() => {
+                    return Ok(InputResult::Eof);
+                }
+                // An unterminated last line still runs
+                Key::Eof => {
                     self.write_output(&mut stdout, CRLF.as_bytes())?;
                     break;
                 }
-                BACK_SPACE | CTRL_H => {
-                    if !buffer.is_empty() {
-                        buffer.pop();
-                        self.write_output(&mut stdout, b"\x08 \x08")?;
+                Key::Char(c) => {
+                    buffer.insert(c);
+                    self.echo(&mut stdout, &prompt, &buffer, c.encode_utf8(&mut [0; 4]))?;
+                }
+                Key::Up | Key::PageUp => {
+                    match navigator.previous(buffer.as_str(), key == Key::PageUp) {
+                        Some(entry) => {
+                            buffer.set(entry);
+                            self.refresh_line(&mut stdout, &prompt, &buffer)?;
+                        }
+                        None => self.write_output(&mut stdout, BELL_CHAR.as_bytes())?,
                     }
-                    is_tab_pressed = false;
                 }
-                CTRL_C => {
-                    return Ok(InputResult::Reset);
+                Key::Down | Key::PageDown => match navigator.next(key == Key::PageDown) {
+                    Some(entry) => {
+                        buffer.set(entry);
+                        self.refresh_line(&mut stdout, &prompt, &buffer)?;
+                    }
+                    None => self.write_output(&mut stdout, BELL_CHAR.as_bytes())?,
+                },
+                Key::Ctrl('r') | Key::Ctrl('s') => {
+                    let direction = match key {
+                        Key::Ctrl('s') => SearchDirection::Forward,
+                        _ => SearchDirection::Backward,
+                    };
+                    pending_key =
+                        self.search(stdin, &mut stdout, &prompt, &mut buffer, direction)?;
                 }
-                c if c >= ASCII_SPACE && c < ASCII_DEL => {
-                    buffer.push(c as char);
-                    self.write_output(&mut stdout, &[c])?;
-                    is_tab_pressed = false;
+                Key::Ctrl('l') => {
+                    self.renderer
+                        .render(&mut stdout, &prompt, &buffer, Repaint::Screen)?
                 }
-                _ => {
-                    is_tab_pressed = false;
+                Key::Signal => self.refresh_line(&mut stdout, &prompt, &buffer)?,
+                Key::Ctrl('k' | 'u' | 'w' | 'y') | Key::Alt('d' | 'y') | Key::AltBackspace => {
+                    last_edit = self.kill_or_yank(&mut buffer, key, previous_edit);
+                    self.refresh_line(&mut stdout, &prompt, &buffer)?;
+                }
+                key => {
+                    if Self::edit(&mut buffer, key) {
+                        self.refresh_line(&mut stdout, &prompt, &buffer)?;
+                    }
                 }
             }
         }
 
-        Ok(InputResult::Input(buffer))
+        Ok(InputResult::Input(buffer.into_string()))
+    }
+
+    /// Applies cursor movement and deletion keys, returning whether the
+    /// line needs to be redrawn.
+    fn edit(buffer: &mut LineBuffer, key: Key) -> bool {
+        match key {
+            Key::Backspace => buffer.delete_backward(),
+            Key::Delete | Key::Ctrl('d') => buffer.delete_forward(),
+            Key::Left | Key::Ctrl('b') => buffer.move_left(),
+            Key::Right | Key::Ctrl('f') => buffer.move_right(),
+            Key::Home | Key::Ctrl('a') => {
+                buffer.move_home();
+                true
+            }
+            Key::End | Key::Ctrl('e') => {
+                buffer.move_end();
+                true
+            }
+            Key::Alt('b') => {
+                buffer.move_word_left();
+                true
+            }
+            Key::Alt('f') => {
+                buffer.move_word_right();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn kill_or_yank(&self, buffer: &mut LineBuffer, key: Key, previous: LastEdit) -> LastEdit {
+        let mut kill_ring = self.kill_ring.borrow_mut();
+
+        let (killed, direction) = match key {
+            Key::Ctrl('k') => (buffer.kill_to_end(), KillDirection::Forward),
+            Key::Alt('d') => (buffer.kill_word_forward(), KillDirection::Forward),
+            Key::Ctrl('u') => (buffer.kill_to_start(), KillDirection::Backward),
+            Key::Ctrl('w') => (buffer.kill_blank_word_backward(), KillDirection::Backward),
+            Key::AltBackspace => (buffer.kill_word_backward(), KillDirection::Backward),
+            Key::Ctrl('y') => {
+                let Some(text) = kill_ring.yank() else {
+                    return LastEdit::Other;
+                };
+                let start = buffer.cursor();
+                buffer.insert_str(text);
+                return LastEdit::Yank(start..buffer.cursor());
+            }
+            // Alt-Y only rotates right after a yank
+            Key::Alt('y') => {
+                let LastEdit::Yank(range) = previous else {
+                    return LastEdit::Other;
+                };
+                let Some(text) = kill_ring.rotate() else {
+                    return LastEdit::Other;
+                };
+                let start = range.start;
+                buffer.replace(range, text);
+                return LastEdit::Yank(start..buffer.cursor());
+            }
+            _ => return LastEdit::Other,
+        };
+
+        kill_ring.kill(&killed, direction, previous == LastEdit::Kill);
+        LastEdit::Kill
+    }
+
+    /// Runs an incremental search until a key outside of the search is
+    /// pressed. That key is returned so it can be handled on the accepted
+    /// line, Ctrl-G restores the original buffer instead.
+    fn search(
+        &self,
+        reader: &mut KeyReader<impl KeyInput>,
+        writer: &mut impl Write,
+        prompt: &str,
+        buffer: &mut LineBuffer,
+        direction: SearchDirection,
+    ) -> Result<Option<Key>, ShellError> {
+        let mut search = HistorySearch::new(Arc::clone(&self.history), direction);
+
+        loop {
+            let display = LineBuffer::default();
+            self.renderer
+                .render(writer, &search.display(), &display, Repaint::Line)?;
+
+            match Key::read(reader)? {
+                Key::Signal => {}
+                Key::Ctrl('r') => search.search_again(SearchDirection::Backward),
+                Key::Ctrl('s') => search.search_again(SearchDirection::Forward),
+                Key::Char(c) => search.push(c),
+                Key::Backspace => search.pop(),
+                Key::Ctrl('g') => {
+                    self.refresh_line(writer, prompt, buffer)?;
+                    return Ok(None);
+                }
+                key => {
+                    if let Some(matched) = search.matched() {
+                        buffer.set(matched);
+                    }
+                    self.refresh_line(writer, prompt, buffer)?;
+                    return Ok(Some(key));
+                }
+            }
+        }
+    }
+
+    /// Shows a history entry found in vi mode with the cursor on its first
+    /// character, rings the bell when there is none.
+    fn show_entry(
+        &self,
+        writer: &mut impl Write,
+        prompt: &str,
+        buffer: &mut LineBuffer,
+        entry: Option<String>,
+    ) -> Result<(), ShellError> {
+        match entry {
+            Some(entry) => {
+                buffer.set(entry);
+                buffer.move_home();
+            }
+            None => self.write_output(writer, BELL_CHAR.as_bytes())?,
+        }
+        self.refresh_line(writer, prompt, buffer)
+    }
+
+    /// Reads a `/` or `?` pattern and searches the history for it, `None`
+    /// when the search is cancelled.
+    fn vi_search(
+        &self,
+        reader: &mut KeyReader<impl KeyInput>,
+        writer: &mut impl Write,
+        direction: SearchDirection,
+    ) -> Result<Option<(HistorySearch, SearchDirection)>, ShellError> {
+        let prompt = match direction {
+            SearchDirection::Backward => "/",
+            SearchDirection::Forward => "?",
+        };
+
+        let mut pattern = LineBuffer::default();
+        loop {
+            self.refresh_line(writer, prompt, &pattern)?;
+            match Key::read(reader)? {
+                Key::Enter => break,
+                Key::Char(c) => pattern.insert(c),
+                Key::Backspace if !pattern.is_empty() => {
+                    pattern.delete_backward();
+                }
+                Key::Backspace | Key::Interrupt | Key::Ctrl('g') | Key::Eof => return Ok(None),
+                _ => {}
+            }
+        }
+
+        let mut search = HistorySearch::new(Arc::clone(&self.history), direction);
+        pattern.as_str().chars().for_each(|c| search.push(c));
+        Ok(Some((search, direction)))
+    }
+
+    /// Opens the line in `$VISUAL` or `$EDITOR` and accepts what was saved.
+    fn edit_in_editor(
+        &self,
+        writer: &mut impl Write,
+        line: &str,
+    ) -> Result<InputResult, ShellError> {
+        let editor = std::env::var("VISUAL")
+            .or_else(|_| std::env::var("EDITOR"))
+            .unwrap_or_else(|_| "vi".to_owned());
+        let mut file = tempfile::Builder::new()
+            .suffix(".sh")
+            .tempfile()
+            .map_err(|err| ShellError::Uncontroled(err.to_string()))?;
+        writeln!(file, "{line}").map_err(|err| ShellError::Uncontroled(err.to_string()))?;
+
+        self.write_output(writer, CRLF.as_bytes())?;
+        let mut words = editor.split_whitespace();
+        let status = std::process::Command::new(words.next().unwrap_or("vi"))
+            .args(words)
+            .arg(file.path())
+            .status();
+        match status {
+            Ok(status) if status.success() => {}
+            // The editor said why it failed, the line is dropped
+            Ok(_) => return Ok(InputResult::Input(String::new())),
+            Err(_) => {
+                self.output_handler.handle(
+                    CommandResult::Error(ShellError::CommandNotFound(editor)),
+                    None,
+                )?;
+                return Ok(InputResult::Input(String::new()));
+            }
+        }
+
+        let edited = std::fs::read_to_string(file.path())
+            .map_err(|err| ShellError::Uncontroled(err.to_string()))?;
+        let edited = edited.trim_end_matches('\n').to_owned();
+        self.output_handler.write_stdout(&format!("{edited}\n"));
+        Ok(InputResult::Input(edited))
+    }
+
+    /// Text inserted at the end of the line is echoed as is, anything else
+    /// redraws the line.
+    fn echo(
+        &self,
+        writer: &mut impl Write,
+        prompt: &str,
+        buffer: &LineBuffer,
+        inserted: &str,
+    ) -> Result<(), ShellError> {
+        if buffer.is_at_end() {
+            return self.renderer.append(writer, prompt, buffer, inserted);
+        }
+        self.refresh_line(writer, prompt, buffer)
+    }
+
+    /// Lists the matches of a second Tab under the line. Past `QUERY_ITEMS`
+    /// matches the user is asked first, and listings taller than the
+    /// screen are paged above a new prompt instead. The matches replace
+    /// the `word` bytes before the cursor.
+    fn show_menu(
+        &self,
+        reader: &mut KeyReader<impl KeyInput>,
+        writer: &mut impl Write,
+        prompt: &str,
+        buffer: &LineBuffer,
+        menu: CompletionMenu,
+        word: usize,
+    ) -> Result<Option<MenuState>, ShellError> {
+        let size = terminal::size();
+        let layout = menu.layout(size.columns);
+        let line_rows = Renderer::rows(&format!("{prompt}{}", buffer.as_str()));
+        let mut state = MenuState {
+            menu,
+            start: buffer.cursor() - word,
+            replaced: word,
+            shown: true,
+        };
+
+        if state.menu.len() < QUERY_ITEMS && line_rows + Renderer::rows(&layout) < size.lines {
+            self.renderer.render_menu(writer, prompt, buffer, &layout)?;
+            return Ok(Some(state));
+        }
+
+        state.shown = false;
+        let listed =
+            state.menu.len() < QUERY_ITEMS || self.query(reader, writer, state.menu.len())?;
+        self.write_output(writer, CRLF.as_bytes())?;
+        if listed {
+            self.page(reader, writer, &layout)?;
+        }
+        self.renderer
+            .render(writer, prompt, buffer, Repaint::Fresh)?;
+        Ok(listed.then_some(state))
+    }
+
+    fn query(
+        &self,
+        reader: &mut KeyReader<impl KeyInput>,
+        writer: &mut impl Write,
+        count: usize,
+    ) -> Result<bool, ShellError> {
+        let question = format!("{CRLF}Display all {count} possibilities? (y or n)");
+        self.write_output(writer, question.as_bytes())?;
+        loop {
+            match Key::read(reader)? {
+                Key::Char('y' | 'Y' | ' ') => return Ok(true),
+                Key::Char('n' | 'N') | Key::Backspace | Key::Interrupt | Key::Eof => {
+                    return Ok(false)
+                }
+                Key::Signal => {}
+                _ => self.write_output(writer, BELL_CHAR.as_bytes())?,
+            }
+        }
+    }
+
+    /// Writes a screen of `listing` at a time. At `--More--`, Space shows
+    /// the next screen, Enter the next row and q stops.
+    fn page(
+        &self,
+        reader: &mut KeyReader<impl KeyInput>,
+        writer: &mut impl Write,
+        listing: &str,
+    ) -> Result<(), ShellError> {
+        let screen = terminal::size().lines.saturating_sub(1).max(1);
+        let mut rows = listing.split('\n').peekable();
+        let mut count = screen;
+
+        loop {
+            for row in rows.by_ref().take(count) {
+                self.write_output(writer, format!("{row}{CRLF}").as_bytes())?;
+            }
+            if rows.peek().is_none() {
+                return Ok(());
+            }
+
+            self.write_output(writer, MORE_PROMPT.as_bytes())?;
+            let key = Key::read(reader)?;
+            self.write_output(writer, b"\r\x1b[K")?;
+            count = match key {
+                Key::Char(' ') => screen,
+                Key::Enter => 1,
+                Key::Char('q' | 'Q' | 'n' | 'N') | Key::Interrupt | Key::Eof => return Ok(()),
+                _ => {
+                    self.write_output(writer, BELL_CHAR.as_bytes())?;
+                    0
+                }
+            };
+        }
+    }
+
+    /// Replaces the word with the next or previous match of the menu.
+    fn cycle(
+        &self,
+        writer: &mut impl Write,
+        prompt: &str,
+        buffer: &mut LineBuffer,
+        state: &mut MenuState,
+        forward: bool,
+    ) -> Result<(), ShellError> {
+        let item = if forward {
+            state.menu.select_next()
+        } else {
+            state.menu.select_previous()
+        };
+        let Some(replacement) = item.map(|item| item.replacement.clone()) else {
+            return Ok(());
+        };
+
+        buffer.replace(state.start..state.start + state.replaced, &replacement);
+        state.replaced = replacement.len();
+        if state.shown {
+            let layout = state.menu.layout(terminal::size().columns);
+            return self.renderer.render_menu(writer, prompt, buffer, &layout);
+        }
+        self.refresh_line(writer, prompt, buffer)
+    }
+
+    fn refresh_line(
+        &self,
+        writer: &mut impl Write,
+        prompt: &str,
+        buffer: &LineBuffer,
+    ) -> Result<(), ShellError> {
+        self.renderer.render(writer, prompt, buffer, Repaint::Line)
     }
 
     fn write_output(&self, writer: &mut impl Write, buffer: &[u8]) -> Result<(), ShellError> {
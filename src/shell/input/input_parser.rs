@@ -1,6 +1,7 @@
-use std::{char, path::PathBuf, sync::Arc, usize};
+use std::{char, collections::HashSet, path::PathBuf, sync::Arc};
 
 use crate::exceptions::commands::ShellError;
+use crate::shell::alias::AliasTable;
 use crate::shell::file::FileManager;
 use crate::shell::input::commons::{BACK_SLASH, DOUBLE_QUOTE, SINGLE_QUOTE};
 use crate::shell::input::quote::{QuotePosition, QuoteType};
@@ -41,6 +42,7 @@ impl<'a> ParserState<'a> {
 
 pub struct InputParser {
     file_manager: Arc<FileManager>,
+    aliases: Arc<AliasTable>,
 }
 
 #[derive(Debug)]
@@ -62,7 +64,63 @@ impl ParsedCommand {
 
 impl InputParser {
     pub fn new(file_manager: Arc<FileManager>) -> Self {
-        Self { file_manager }
+        Self {
+            file_manager,
+            aliases: Arc::new(AliasTable::default()),
+        }
+    }
+
+    pub fn with_aliases(mut self, aliases: Arc<AliasTable>) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    /// Replaces the first word of a simple command with its alias value.
+    /// Quoted or escaped words are never expanded and an alias is expanded
+    /// at most once within a word's own chain, so `alias ls='ls -F'` does
+    /// not loop. The aliases in `expanding`, whose text is being run, are
+    /// left alone and the ones expanded here are added to it.
+    pub fn expand_aliases(&self, input: &str, expanding: &mut HashSet<String>) -> String {
+        let running = expanding.clone();
+        self.expand_first_word(input, &running, &mut running.clone(), expanding)
+    }
+
+    fn expand_first_word(
+        &self,
+        input: &str,
+        running: &HashSet<String>,
+        seen: &mut HashSet<String>,
+        expanding: &mut HashSet<String>,
+    ) -> String {
+        let rest = input.trim_start();
+        let (leading, rest) = input.split_at(input.len() - rest.len());
+        let word_end = rest
+            .find(|char: char| {
+                char.is_whitespace() || matches!(char, ';' | '&' | '|' | '<' | '>' | '(' | ')')
+            })
+            .unwrap_or(rest.len());
+        let (word, tail) = rest.split_at(word_end);
+
+        if word.contains([SINGLE_QUOTE, DOUBLE_QUOTE, BACK_SLASH]) || seen.contains(word) {
+            return input.to_owned();
+        }
+        let Some(value) = self.aliases.get(word) else {
+            return input.to_owned();
+        };
+
+        seen.insert(word.to_owned());
+        expanding.insert(word.to_owned());
+        let expanded = self.expand_first_word(&value, running, seen, expanding);
+
+        // A chain ending in a blank makes the next word eligible too, with
+        // a chain of its own
+        let tail = if expanded.ends_with(char::is_whitespace) {
+            self.expand_first_word(tail, running, &mut running.clone(), expanding)
+        } else {
+            tail.to_owned()
+        };
+
+        format!("{leading}{expanded}{tail}")
     }
 
     fn quote_positions(&self, args: &str) -> Result<Vec<QuotePosition>, ShellError> {
@@ -133,7 +191,6 @@ impl InputParser {
             return;
         }
         parser_state.push_literal(char);
-        return;
     }
 
     fn handle_regular(&self, parser_state: &mut ParserState, args: &str, idx: usize, char: char) {
@@ -168,7 +225,7 @@ impl InputParser {
                     .find(|pos| idx > *pos.start() && idx < *pos.end());
 
                 if let Some(quote_pos) = maybe_quote_pos {
-                    parser_state.set_quote_position(&quote_pos);
+                    parser_state.set_quote_position(quote_pos);
                     self.handle_quote(&mut parser_state, char);
                     continue;
                 }
@@ -216,10 +273,10 @@ impl InputParser {
         let quote_positions = self.quote_positions(input)?;
         let mut parsed_args = self.parse_args(&quote_positions, input);
         let maybe_redirection = self.parse_redirection(&mut parsed_args)?;
-        let command = &parsed_args[0];
+        let command = parsed_args.first().map_or("", String::as_str);
 
         Ok((
-            ParsedCommand::new(command, parsed_args[1..].to_vec()),
+            ParsedCommand::new(command, parsed_args.iter().skip(1).cloned().collect()),
             maybe_redirection,
         ))
     }
@@ -1319,4 +1376,57 @@ mod tests {
             "Expected Unknown error for missing filename after >"
         );
     }
+
+    // ========================================================================
+    // Alias Expansion Tests
+    // ========================================================================
+
+    fn expand(parser: &InputParser, input: &str) -> String {
+        parser.expand_aliases(input, &mut HashSet::new())
+    }
+
+    fn alias_parser() -> InputParser {
+        let aliases = Arc::new(AliasTable::default());
+        aliases.set("ll", "ls -la");
+        aliases.set("ls", "ls -F");
+        aliases.set("l", "ll");
+        aliases.set("sudo", "sudo ");
+        aliases.set("a", "b");
+        aliases.set("b", "a");
+        aliases.set("run", "go");
+        aliases.set("go", "echo ");
+        aliases.set("x", "foo");
+        InputParser::new(Arc::new(FileManager)).with_aliases(aliases)
+    }
+
+    #[test]
+    fn expand_alias_in_command_position() {
+        let parser = alias_parser();
+        assert_eq!(expand(&parser, "ll /tmp > out"), "ls -F -la /tmp > out");
+        assert_eq!(expand(&parser, "echo ll"), "echo ll");
+    }
+
+    #[test]
+    fn expand_alias_recursively_with_loop_protection() {
+        let parser = alias_parser();
+        assert_eq!(expand(&parser, "l"), "ls -F -la");
+        assert_eq!(expand(&parser, "a"), "a");
+    }
+
+    #[test]
+    fn expand_alias_after_trailing_blank() {
+        let parser = alias_parser();
+        assert_eq!(expand(&parser, "sudo ll"), "sudo  ls -F -la");
+        // The next word's chain doesn't share the first one's aliases
+        assert_eq!(expand(&parser, "sudo sudo ll"), "sudo  sudo  ls -F -la");
+        // The blank ends the chain, not the first alias
+        assert_eq!(expand(&parser, "run x"), "echo  foo");
+    }
+
+    #[test]
+    fn quoted_word_is_not_expanded() {
+        let parser = alias_parser();
+        assert_eq!(expand(&parser, "'ll'"), "'ll'");
+        assert_eq!(expand(&parser, "\\ll"), "\\ll");
+    }
 }
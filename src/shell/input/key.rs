@@ -0,0 +1,321 @@
+use std::{
+    collections::VecDeque,
+    io::{self, Read},
+};
+
+use crate::{
+    exceptions::commands::ShellError,
+    shell::input::commons::{
+        ASCII_DEL, ASCII_SPACE, BACK_SPACE, CARRIAGE, CTRL_C, CTRL_H, ESCAPE, LINEBREAK, TABULATION,
+    },
+};
+
+/// How long the rest of an escape sequence may take to arrive after ESC,
+/// in milliseconds. Past it, ESC is a key of its own.
+const ESCAPE_TIMEOUT: i32 = 50;
+
+/// Where keys are read from, which can tell whether more input is on its
+/// way.
+pub trait KeyInput: Read {
+    /// Whether input can be read within `timeout` milliseconds.
+    fn poll(&self, timeout: i32) -> bool;
+}
+
+impl KeyInput for &[u8] {
+    fn poll(&self, _timeout: i32) -> bool {
+        !self.is_empty()
+    }
+}
+
+/// The standard input, read past the buffer of `io::Stdin` so that it can
+/// be polled.
+pub struct RawStdin;
+
+impl Read for RawStdin {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = unsafe { libc::read(libc::STDIN_FILENO, buf.as_mut_ptr().cast(), buf.len()) };
+        if read < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(read as usize)
+    }
+}
+
+impl KeyInput for RawStdin {
+    fn poll(&self, timeout: i32) -> bool {
+        let mut fd = libc::pollfd {
+            fd: libc::STDIN_FILENO,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        unsafe { libc::poll(&mut fd, 1, timeout) > 0 }
+    }
+}
+
+/// Reads keys from `input`, keeping what was read ahead of the key for
+/// the next ones.
+pub struct KeyReader<R> {
+    input: R,
+    buffer: VecDeque<u8>,
+}
+
+impl<R: KeyInput> KeyReader<R> {
+    pub fn new(input: R) -> Self {
+        Self {
+            input,
+            buffer: VecDeque::new(),
+        }
+    }
+
+    fn poll(&self, timeout: i32) -> bool {
+        !self.buffer.is_empty() || self.input.poll(timeout)
+    }
+
+    fn unread(&mut self, byte: u8) {
+        self.buffer.push_front(byte);
+    }
+}
+
+impl<R: KeyInput> Read for KeyReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.buffer.is_empty() {
+            let mut chunk = [0u8; 1024];
+            let read = self.input.read(&mut chunk)?;
+            self.buffer.extend(&chunk[..read]);
+        }
+        self.buffer.read(buf)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Ctrl(char),
+    Alt(char),
+    AltBackspace,
+    Enter,
+    Tab,
+    /// ESC on its own, or followed by a key it doesn't combine with.
+    Escape,
+    /// Shift-Tab.
+    BackTab,
+    Backspace,
+    Interrupt,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    Delete,
+    PageUp,
+    PageDown,
+    Eof,
+    /// A signal such as SIGWINCH arrived while waiting for a key.
+    Signal,
+    Unknown,
+}
+
+impl Key {
+    /// Reads a single key press, decoding `ESC [` and `ESC O` sequences.
+    /// The end of the input is reported as `Key::Eof`.
+    pub fn read(reader: &mut KeyReader<impl KeyInput>) -> Result<Self, ShellError> {
+        let mut byte = [0u8; 1];
+        let byte = match reader.read(&mut byte) {
+            Ok(0) => return Ok(Self::Eof),
+            Ok(_) => byte[0],
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => return Ok(Self::Signal),
+            Err(err) => return Err(ShellError::Uncontroled(err.to_string())),
+        };
+
+        let key = match byte {
+            TABULATION => Self::Tab,
+            CARRIAGE | LINEBREAK => Self::Enter,
+            BACK_SPACE | CTRL_H => Self::Backspace,
+            CTRL_C => Self::Interrupt,
+            ESCAPE => Self::read_escape(reader)?,
+            byte if (ASCII_SPACE..ASCII_DEL).contains(&byte) => Self::Char(byte as char),
+            byte @ 1..=26 => Self::Ctrl((b'a' + byte - 1) as char),
+            byte @ 0xc2..=0xf4 => Self::read_utf8(reader, byte)?,
+            _ => Self::Unknown,
+        };
+        Ok(key)
+    }
+
+    /// Reads the continuation bytes of a multi-byte UTF-8 character.
+    fn read_utf8(reader: &mut KeyReader<impl KeyInput>, lead: u8) -> Result<Self, ShellError> {
+        let length = match lead {
+            0xc2..=0xdf => 2,
+            0xe0..=0xef => 3,
+            _ => 4,
+        };
+
+        let mut bytes = vec![lead];
+        for _ in 1..length {
+            match Self::read_byte(reader)? {
+                Some(byte @ 0x80..=0xbf) => bytes.push(byte),
+                // Not a continuation byte, so it starts the next key
+                Some(byte) => {
+                    reader.unread(byte);
+                    return Ok(Self::Unknown);
+                }
+                None => return Ok(Self::Unknown),
+            }
+        }
+
+        let key = std::str::from_utf8(&bytes)
+            .ok()
+            .and_then(|text| text.chars().next())
+            .map_or(Self::Unknown, Self::Char);
+        Ok(key)
+    }
+
+    fn read_escape(reader: &mut KeyReader<impl KeyInput>) -> Result<Self, ShellError> {
+        if !reader.poll(ESCAPE_TIMEOUT) {
+            return Ok(Self::Escape);
+        }
+
+        match Self::read_byte(reader)? {
+            Some(b'[' | b'O') => {}
+            Some(BACK_SPACE | CTRL_H) => return Ok(Self::AltBackspace),
+            Some(byte) if (ASCII_SPACE..ASCII_DEL).contains(&byte) => {
+                return Ok(Self::Alt(byte as char))
+            }
+            // Such as Enter or another ESC, read as the next key
+            Some(byte) => {
+                reader.unread(byte);
+                return Ok(Self::Escape);
+            }
+            None => return Ok(Self::Escape),
+        }
+
+        // Parameter bytes are followed by a single final byte, a sequence
+        // cut short by the end of the input is ignored
+        let mut params = Vec::new();
+        let final_byte = loop {
+            match Self::read_byte(reader)? {
+                Some(byte @ 0x30..=0x3f) => params.push(byte),
+                Some(byte) => break byte,
+                None => return Ok(Self::Unknown),
+            }
+        };
+
+        let key = match (params.as_slice(), final_byte) {
+            (_, b'A') => Self::Up,
+            (_, b'B') => Self::Down,
+            (_, b'C') => Self::Right,
+            (_, b'D') => Self::Left,
+            (_, b'H') | (b"1" | b"7", b'~') => Self::Home,
+            (_, b'F') | (b"4" | b"8", b'~') => Self::End,
+            (b"3", b'~') => Self::Delete,
+            (b"5", b'~') => Self::PageUp,
+            (b"6", b'~') => Self::PageDown,
+            (_, b'Z') => Self::BackTab,
+            _ => Self::Unknown,
+        };
+        Ok(key)
+    }
+
+    /// The next byte, `None` at the end of the input.
+    fn read_byte(reader: &mut KeyReader<impl KeyInput>) -> Result<Option<u8>, ShellError> {
+        let mut byte = [0u8; 1];
+        match reader.read_exact(&mut byte) {
+            Ok(()) => Ok(Some(byte[0])),
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+            Err(err) => Err(ShellError::Uncontroled(err.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(input: &[u8]) -> Vec<Key> {
+        let mut reader = KeyReader::new(input);
+        let mut keys = Vec::new();
+        loop {
+            match Key::read(&mut reader).unwrap() {
+                Key::Eof => return keys,
+                key => keys.push(key),
+            }
+        }
+    }
+
+    #[test]
+    fn decode_plain_keys() {
+        assert_eq!(
+            keys(b"a\t\r\x7f\x03"),
+            vec![
+                Key::Char('a'),
+                Key::Tab,
+                Key::Enter,
+                Key::Backspace,
+                Key::Interrupt
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_multibyte_characters() {
+        assert_eq!(
+            keys("é日🦀".as_bytes()),
+            vec![Key::Char('é'), Key::Char('日'), Key::Char('🦀')]
+        );
+        assert_eq!(keys(b"\xc3x"), vec![Key::Unknown, Key::Char('x')]);
+        assert_eq!(keys(b"\xe6\x97"), vec![Key::Unknown]);
+    }
+
+    #[test]
+    fn decode_control_keys() {
+        assert_eq!(
+            keys(b"\x12\x13\x07"),
+            vec![Key::Ctrl('r'), Key::Ctrl('s'), Key::Ctrl('g')]
+        );
+    }
+
+    #[test]
+    fn decode_escape_sequences() {
+        assert_eq!(
+            keys(b"\x1b[A\x1b[B\x1bOC\x1b[D\x1b[5~\x1b[6~\x1b[3~\x1b[1;5H\x1b[Z"),
+            vec![
+                Key::Up,
+                Key::Down,
+                Key::Right,
+                Key::Left,
+                Key::PageUp,
+                Key::PageDown,
+                Key::Delete,
+                Key::Home,
+                Key::BackTab
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_alt_keys() {
+        assert_eq!(
+            keys(b"\x1bb\x1bf\x1b\x7f"),
+            vec![Key::Alt('b'), Key::Alt('f'), Key::AltBackspace]
+        );
+    }
+
+    #[test]
+    fn decode_standalone_escape() {
+        assert_eq!(keys(b"\x1b"), vec![Key::Escape]);
+        assert_eq!(keys(b"\x1b\r"), vec![Key::Escape, Key::Enter]);
+        assert_eq!(keys(b"\x1b\x1b"), vec![Key::Escape, Key::Escape]);
+        assert_eq!(keys(b"\x1b\x1b[A"), vec![Key::Escape, Key::Up]);
+    }
+
+    #[test]
+    fn unknown_sequence_is_consumed() {
+        assert_eq!(keys(b"\x1b[15~x"), vec![Key::Unknown, Key::Char('x')]);
+    }
+
+    #[test]
+    fn end_of_input() {
+        assert_eq!(Key::read(&mut KeyReader::new(&b""[..])), Ok(Key::Eof));
+        assert_eq!(keys(b"\x1b[1;"), vec![Key::Unknown]);
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::VecDeque;
+
+const KILL_RING_SIZE: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KillDirection {
+    Forward,
+    Backward,
+}
+
+/// Killed text, newest first. Yanking starts at the newest entry and
+/// `rotate` walks towards older ones.
+#[derive(Debug, Default)]
+pub struct KillRing {
+    entries: VecDeque<String>,
+    index: usize,
+}
+
+impl KillRing {
+    /// Stores killed text. Consecutive kills are merged into one entry,
+    /// keeping the text in the order it appeared on the line.
+    pub fn kill(&mut self, text: &str, direction: KillDirection, consecutive: bool) {
+        self.index = 0;
+        match self.entries.front_mut() {
+            Some(front) if consecutive => match direction {
+                KillDirection::Forward => front.push_str(text),
+                KillDirection::Backward => front.insert_str(0, text),
+            },
+            _ if text.is_empty() => {}
+            _ => {
+                self.entries.push_front(text.to_owned());
+                self.entries.truncate(KILL_RING_SIZE);
+            }
+        }
+    }
+
+    pub fn yank(&mut self) -> Option<&str> {
+        self.index = 0;
+        self.entries.front().map(String::as_str)
+    }
+
+    pub fn rotate(&mut self) -> Option<&str> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        self.index = (self.index + 1) % self.entries.len();
+        self.entries.get(self.index).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consecutive_kills_accumulate() {
+        let mut ring = KillRing::default();
+        ring.kill("world", KillDirection::Backward, false);
+        ring.kill("hello ", KillDirection::Backward, true);
+        ring.kill("!", KillDirection::Forward, true);
+        assert_eq!(ring.yank(), Some("hello world!"));
+    }
+
+    #[test]
+    fn rotate_through_older_kills() {
+        let mut ring = KillRing::default();
+        ring.kill("one", KillDirection::Forward, false);
+        ring.kill("two", KillDirection::Forward, false);
+        assert_eq!(ring.yank(), Some("two"));
+        assert_eq!(ring.rotate(), Some("one"));
+        assert_eq!(ring.rotate(), Some("two"));
+    }
+
+    #[test]
+    fn ring_is_bounded() {
+        let mut ring = KillRing::default();
+        for idx in 0..=KILL_RING_SIZE {
+            ring.kill(&idx.to_string(), KillDirection::Forward, false);
+        }
+        assert_eq!(ring.entries.len(), KILL_RING_SIZE);
+        assert_eq!(ring.yank(), Some("10"));
+    }
+
+    #[test]
+    fn empty_ring() {
+        let mut ring = KillRing::default();
+        ring.kill("", KillDirection::Forward, false);
+        assert_eq!(ring.yank(), None);
+        assert_eq!(ring.rotate(), None);
+    }
+}
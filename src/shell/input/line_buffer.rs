@@ -0,0 +1,270 @@
+use std::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Text of the line being edited and the cursor, as a byte offset that is
+/// always on a grapheme cluster boundary.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LineBuffer {
+    text: String,
+    cursor: usize,
+}
+
+impl LineBuffer {
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        let cursor = text.len();
+        Self { text, cursor }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn into_string(self) -> String {
+        self.text
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.cursor == self.text.len()
+    }
+
+    pub fn before_cursor(&self) -> &str {
+        &self.text[..self.cursor]
+    }
+
+    pub fn after_cursor(&self) -> &str {
+        &self.text[self.cursor..]
+    }
+
+    /// Replaces the whole line, leaving the cursor at the end.
+    pub fn set(&mut self, text: impl Into<String>) {
+        *self = Self::new(text);
+    }
+
+    pub fn insert(&mut self, char: char) {
+        self.text.insert(self.cursor, char);
+        self.cursor += char.len_utf8();
+    }
+
+    pub fn insert_str(&mut self, text: &str) {
+        self.text.insert_str(self.cursor, text);
+        self.cursor += text.len();
+    }
+
+    pub fn delete_backward(&mut self) -> bool {
+        let end = self.cursor;
+        self.move_left() && !self.drain(self.cursor..end).is_empty()
+    }
+
+    pub fn delete_forward(&mut self) -> bool {
+        let start = self.cursor;
+        self.move_right() && !self.drain(start..self.cursor).is_empty()
+    }
+
+    pub fn move_left(&mut self) -> bool {
+        match self.before_cursor().graphemes(true).next_back() {
+            Some(grapheme) => {
+                self.cursor -= grapheme.len();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn move_right(&mut self) -> bool {
+        match self.after_cursor().graphemes(true).next() {
+            Some(grapheme) => {
+                self.cursor += grapheme.len();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves to `cursor`, which must be on a grapheme cluster boundary.
+    pub fn move_to(&mut self, cursor: usize) {
+        self.cursor = cursor.min(self.text.len());
+    }
+
+    pub fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.cursor = self.text.len();
+    }
+
+    /// Moves to the start of the current or previous word.
+    pub fn move_word_left(&mut self) {
+        self.cursor = self.word_start();
+    }
+
+    /// Moves past the end of the current or next word.
+    pub fn move_word_right(&mut self) {
+        self.cursor = self.word_end();
+    }
+
+    /// Replaces `range` with `text`, leaving the cursor after it.
+    pub fn replace(&mut self, range: Range<usize>, text: &str) {
+        self.text.replace_range(range.clone(), text);
+        self.cursor = range.start + text.len();
+    }
+
+    pub fn kill_to_end(&mut self) -> String {
+        self.drain(self.cursor..self.text.len())
+    }
+
+    pub fn kill_to_start(&mut self) -> String {
+        self.drain(0..self.cursor)
+    }
+
+    pub fn kill_word_forward(&mut self) -> String {
+        self.drain(self.cursor..self.word_end())
+    }
+
+    /// Kills back to the previous alphanumeric word start (Alt-Backspace).
+    pub fn kill_word_backward(&mut self) -> String {
+        self.drain(self.word_start()..self.cursor)
+    }
+
+    /// Kills back to the previous blank (Ctrl-W).
+    pub fn kill_blank_word_backward(&mut self) -> String {
+        let trimmed = self.before_cursor().trim_end_matches(char::is_whitespace);
+        let start = Self::after_last(trimmed, char::is_whitespace);
+        self.drain(start..self.cursor)
+    }
+
+    fn drain(&mut self, range: Range<usize>) -> String {
+        self.cursor = range.start;
+        self.text.drain(range).collect()
+    }
+
+    fn word_end(&self) -> usize {
+        let after = self.after_cursor();
+        let word_start = after.find(Self::is_word_char).unwrap_or(after.len());
+        let word_end = after[word_start..]
+            .find(|char: char| !Self::is_word_char(char))
+            .map_or(after.len(), |end| word_start + end);
+        self.cursor + word_end
+    }
+
+    fn word_start(&self) -> usize {
+        let trimmed = self
+            .before_cursor()
+            .trim_end_matches(|char: char| !Self::is_word_char(char));
+        Self::after_last(trimmed, |char| !Self::is_word_char(char))
+    }
+
+    /// Byte offset right after the last character matching `predicate`.
+    fn after_last(text: &str, predicate: impl Fn(char) -> bool) -> usize {
+        text.char_indices()
+            .rev()
+            .find(|&(_, char)| predicate(char))
+            .map_or(0, |(idx, char)| idx + char.len_utf8())
+    }
+
+    fn is_word_char(char: char) -> bool {
+        char.is_alphanumeric() || char == '_'
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_in_the_middle() {
+        let mut buffer = LineBuffer::new("eco");
+        buffer.move_left();
+        buffer.insert('h');
+        assert_eq!(buffer.as_str(), "echo");
+        assert_eq!(buffer.after_cursor(), "o");
+    }
+
+    #[test]
+    fn delete_around_cursor() {
+        let mut buffer = LineBuffer::new("abcd");
+        buffer.move_left();
+        buffer.move_left();
+        assert!(buffer.delete_backward());
+        assert!(buffer.delete_forward());
+        assert_eq!(buffer.as_str(), "ad");
+
+        buffer.move_home();
+        assert!(!buffer.delete_backward());
+        buffer.move_end();
+        assert!(!buffer.delete_forward());
+    }
+
+    #[test]
+    fn word_movement() {
+        let mut buffer = LineBuffer::new("cat some-file  next");
+        buffer.move_word_left();
+        assert_eq!(buffer.after_cursor(), "next");
+        buffer.move_word_left();
+        assert_eq!(buffer.after_cursor(), "file  next");
+        buffer.move_word_left();
+        buffer.move_word_left();
+        assert_eq!(buffer.cursor(), 0);
+
+        buffer.move_word_right();
+        assert_eq!(buffer.after_cursor(), " some-file  next");
+        buffer.move_word_right();
+        assert_eq!(buffer.after_cursor(), "-file  next");
+    }
+
+    #[test]
+    fn kill_commands() {
+        let mut buffer = LineBuffer::new("git commit -m msg");
+        assert_eq!(buffer.kill_blank_word_backward(), "msg");
+        assert_eq!(buffer.kill_word_backward(), "m ");
+        assert_eq!(buffer.as_str(), "git commit -");
+
+        buffer.move_home();
+        assert_eq!(buffer.kill_word_forward(), "git");
+        buffer.move_word_right();
+        assert_eq!(buffer.kill_to_end(), " -");
+        assert_eq!(buffer.kill_to_start(), " commit");
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn replace_range() {
+        let mut buffer = LineBuffer::new("echo one!");
+        buffer.replace(5..8, "three");
+        assert_eq!(buffer.as_str(), "echo three!");
+        assert_eq!(buffer.after_cursor(), "!");
+    }
+
+    #[test]
+    fn move_and_delete_by_grapheme_cluster() {
+        // "e" followed by a combining acute accent, then a flag emoji
+        let mut buffer = LineBuffer::new("ae\u{301}🇫🇷b");
+        buffer.move_left();
+        buffer.move_left();
+        assert_eq!(buffer.after_cursor(), "🇫🇷b");
+        assert!(buffer.delete_backward());
+        assert_eq!(buffer.as_str(), "a🇫🇷b");
+        assert!(buffer.delete_forward());
+        assert_eq!(buffer.as_str(), "ab");
+    }
+
+    #[test]
+    fn multibyte_characters() {
+        let mut buffer = LineBuffer::new("añb");
+        buffer.move_left();
+        buffer.move_left();
+        assert_eq!(buffer.after_cursor(), "ñb");
+        assert!(buffer.delete_forward());
+        assert_eq!(buffer.as_str(), "ab");
+    }
+}
@@ -1,5 +1,14 @@
+pub mod command_list;
+pub mod commons;
+pub mod history_expansion;
+pub mod history_navigator;
+pub mod history_search;
+pub mod input_handler;
 pub mod input_parser;
+pub mod key;
+pub mod kill_ring;
+pub mod line_buffer;
 pub mod quote;
-pub mod commons;
 pub mod redirection_context;
-pub mod input_handler;
+pub mod render;
+pub mod vi;
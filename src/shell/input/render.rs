@@ -0,0 +1,313 @@
+use std::{cell::Cell, io::Write};
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    exceptions::commands::ShellError,
+    shell::{
+        input::{
+            commons::{CRLF, IGNORE_END, IGNORE_START, NORMAL_VIDEO, REVERSE_VIDEO},
+            line_buffer::LineBuffer,
+        },
+        terminal,
+    },
+};
+
+const CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+const CLEAR_TO_END_OF_SCREEN: &str = "\x1b[J";
+
+/// How much of the terminal is repainted along with the editing state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repaint {
+    /// The prompt is written where the cursor is, on a new line.
+    Fresh,
+    /// The rows of the current line are overwritten.
+    Line,
+    /// The screen is cleared and the prompt drawn at the top.
+    Screen,
+}
+
+/// Moves the cursor home and clears the terminal.
+pub fn clear_screen() -> &'static str {
+    CLEAR_SCREEN
+}
+
+/// Paints the prompt and the line, wrapping at the terminal width. The row
+/// the cursor is left on is remembered so the next repaint can start from
+/// the first row of the line.
+#[derive(Debug, Default)]
+pub struct Renderer {
+    cursor_row: Cell<usize>,
+}
+
+impl Renderer {
+    pub fn render(
+        &self,
+        writer: &mut impl Write,
+        prompt: &str,
+        buffer: &LineBuffer,
+        repaint: Repaint,
+    ) -> Result<(), ShellError> {
+        self.paint(writer, prompt, buffer, repaint, "")
+    }
+
+    /// Repaints the line with `menu` listed under it, the cursor is left on
+    /// the line. The next repaint clears the menu.
+    pub fn render_menu(
+        &self,
+        writer: &mut impl Write,
+        prompt: &str,
+        buffer: &LineBuffer,
+        menu: &str,
+    ) -> Result<(), ShellError> {
+        self.paint(writer, prompt, buffer, Repaint::Line, menu)
+    }
+
+    /// Rows taken by `text` once wrapped at the terminal width.
+    pub fn rows(text: &str) -> usize {
+        let columns = terminal::size().columns;
+        text.split('\n')
+            .map(|row| {
+                let row = row.replace(REVERSE_VIDEO, "").replace(NORMAL_VIDEO, "");
+                Self::width(&row).div_ceil(columns).max(1)
+            })
+            .sum()
+    }
+
+    /// Columns `text` takes on screen, leaving out the parts between
+    /// `IGNORE_START` and `IGNORE_END`.
+    pub fn width(text: &str) -> usize {
+        let mut ignored = false;
+        let visible: String = text
+            .chars()
+            .filter(|char| match *char {
+                IGNORE_START => {
+                    ignored = true;
+                    false
+                }
+                IGNORE_END => {
+                    ignored = false;
+                    false
+                }
+                _ => !ignored,
+            })
+            .collect();
+        visible.width()
+    }
+
+    /// `text` as written to the terminal, without the markers.
+    fn printable(text: &str) -> String {
+        text.replace([IGNORE_START, IGNORE_END], "")
+    }
+
+    /// Splits a multi-line prompt before its last row, the only one
+    /// repainted along with the line.
+    fn split_prompt(prompt: &str) -> (String, &str) {
+        match prompt.rfind('\n') {
+            Some(idx) => (
+                Self::printable(&prompt[..=idx]).replace('\n', CRLF),
+                &prompt[idx + 1..],
+            ),
+            None => (String::new(), prompt),
+        }
+    }
+
+    fn paint(
+        &self,
+        writer: &mut impl Write,
+        prompt: &str,
+        buffer: &LineBuffer,
+        repaint: Repaint,
+        menu: &str,
+    ) -> Result<(), ShellError> {
+        let columns = terminal::size().columns;
+        let (head, prompt) = Self::split_prompt(prompt);
+
+        let mut output = match repaint {
+            Repaint::Fresh => head,
+            Repaint::Line => match self.cursor_row.get() {
+                0 => "\r".to_owned(),
+                rows => format!("\x1b[{rows}A\r"),
+            },
+            Repaint::Screen => format!("{}{head}", clear_screen()),
+        };
+        output.push_str(&Self::printable(prompt));
+        output.push_str(buffer.as_str());
+        if repaint == Repaint::Line {
+            output.push_str(CLEAR_TO_END_OF_SCREEN);
+        }
+
+        let end = Self::width(prompt) + buffer.as_str().width();
+        let cursor = Self::width(prompt) + buffer.before_cursor().width();
+        output.push_str(Self::wrap(end, columns));
+        if !menu.is_empty() {
+            // Back up from the last row of the menu to the end of the line
+            output.push_str(&format!(
+                "{CRLF}{}\x1b[{}A\r",
+                menu.replace('\n', CRLF),
+                Self::rows(menu)
+            ));
+            if end % columns > 0 {
+                output.push_str(&format!("\x1b[{}C", end % columns));
+            }
+        }
+
+        let (end_row, cursor_row) = (end / columns, cursor / columns);
+        if end_row > cursor_row {
+            output.push_str(&format!("\x1b[{}A\r", end_row - cursor_row));
+            let column = cursor % columns;
+            if column > 0 {
+                output.push_str(&format!("\x1b[{column}C"));
+            }
+        } else if end > cursor {
+            output.push_str(&format!("\x1b[{}D", end - cursor));
+        }
+        self.cursor_row.set(cursor_row);
+
+        Self::write(writer, &output)
+    }
+
+    /// Writes text typed at the end of the line without repainting it.
+    pub fn append(
+        &self,
+        writer: &mut impl Write,
+        prompt: &str,
+        buffer: &LineBuffer,
+        inserted: &str,
+    ) -> Result<(), ShellError> {
+        let columns = terminal::size().columns;
+        let (_, prompt) = Self::split_prompt(prompt);
+        let end = Self::width(prompt) + buffer.as_str().width();
+        self.cursor_row.set(end / columns);
+        Self::write(writer, &format!("{inserted}{}", Self::wrap(end, columns)))
+    }
+
+    /// Terminals hold the cursor on the last column of a full row, so the
+    /// move to the next row is made explicit.
+    fn wrap(end: usize, columns: usize) -> &'static str {
+        if end > 0 && end % columns == 0 {
+            "\r\n"
+        } else {
+            ""
+        }
+    }
+
+    fn write(writer: &mut impl Write, output: &str) -> Result<(), ShellError> {
+        writer
+            .write_all(output.as_bytes())
+            .and_then(|_| writer.flush())
+            .map_err(|err| ShellError::Uncontroled(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rendered(renderer: &Renderer, buffer: &LineBuffer, repaint: Repaint) -> String {
+        let mut output = Vec::new();
+        renderer.render(&mut output, "$ ", buffer, repaint).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn fresh_prompt_is_written_as_is() {
+        let renderer = Renderer::default();
+        assert_eq!(
+            rendered(&renderer, &LineBuffer::new("ls"), Repaint::Fresh),
+            "$ ls"
+        );
+    }
+
+    #[test]
+    fn line_repaint_restores_cursor() {
+        let mut buffer = LineBuffer::new("echo 日本");
+        buffer.move_left();
+        assert_eq!(
+            rendered(&Renderer::default(), &buffer, Repaint::Line),
+            "\r$ echo 日本\x1b[J\x1b[2D"
+        );
+    }
+
+    #[test]
+    fn screen_repaint_clears_first() {
+        assert_eq!(
+            rendered(
+                &Renderer::default(),
+                &LineBuffer::new("ls"),
+                Repaint::Screen
+            ),
+            "\x1b[H\x1b[2J$ ls"
+        );
+    }
+
+    #[test]
+    fn menu_is_listed_under_the_line() {
+        let mut output = Vec::new();
+        let buffer = LineBuffer::new("cat m");
+        Renderer::default()
+            .render_menu(&mut output, "$ ", &buffer, "main.rs\nmod.rs")
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "\r$ cat m\x1b[J\r\nmain.rs\r\nmod.rs\x1b[2A\r\x1b[7C"
+        );
+        assert_eq!(Renderer::rows("\x1b[7mmain.rs\x1b[27m\nmod.rs"), 2);
+    }
+
+    #[test]
+    fn wrapped_line_is_repainted_from_its_first_row() {
+        let columns = terminal::size().columns;
+        let renderer = Renderer::default();
+        let mut buffer = LineBuffer::new("x".repeat(columns));
+        buffer.move_home();
+
+        // The cursor goes back up to the first row
+        let output = rendered(&renderer, &buffer, Repaint::Fresh);
+        assert!(output.ends_with("\x1b[1A\r\x1b[2C"), "got: {output:?}");
+
+        buffer.move_end();
+        let output = rendered(&renderer, &buffer, Repaint::Line);
+        assert!(output.starts_with("\r$ xx"), "got: {output:?}");
+        assert!(output.ends_with("\x1b[J"), "got: {output:?}");
+
+        let output = rendered(&renderer, &buffer, Repaint::Line);
+        assert!(output.starts_with("\x1b[1A\r$ "), "got: {output:?}");
+    }
+
+    #[test]
+    fn prompt_markers_take_no_width() {
+        let prompt = "\x01\x1b[32m\x02$ \x01\x1b[0m\x02";
+        let mut buffer = LineBuffer::new("ls");
+        buffer.move_left();
+        let mut output = Vec::new();
+        Renderer::default()
+            .render(&mut output, prompt, &buffer, Repaint::Line)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "\r\x1b[32m$ \x1b[0mls\x1b[J\x1b[1D"
+        );
+        assert_eq!(Renderer::width(prompt), 2);
+    }
+
+    #[test]
+    fn only_the_last_prompt_row_is_repainted() {
+        let renderer = Renderer::default();
+        let buffer = LineBuffer::new("ls");
+        let mut output = Vec::new();
+        renderer
+            .render(&mut output, "~/src\n$ ", &buffer, Repaint::Fresh)
+            .unwrap();
+        renderer
+            .render(&mut output, "~/src\n$ ", &buffer, Repaint::Line)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "~/src\r\n$ ls\r$ ls\x1b[J"
+        );
+    }
+}
@@ -0,0 +1,668 @@
+use std::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::shell::input::{history_search::SearchDirection, line_buffer::LineBuffer};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ViMode {
+    #[default]
+    Insert,
+    Normal,
+}
+
+impl ViMode {
+    pub fn indicator(&self) -> &'static str {
+        match self {
+            ViMode::Insert => "(ins)",
+            ViMode::Normal => "(cmd)",
+        }
+    }
+}
+
+/// What is left for the input handler to do after a normal mode key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViAction {
+    /// The command needs more keys.
+    Pending,
+    Redraw,
+    Bell,
+    HistoryPrevious,
+    HistoryNext,
+    Search(SearchDirection),
+    SearchAgain {
+        reverse: bool,
+    },
+    Edit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Motion {
+    Left,
+    Right,
+    WordForward,
+    WordBackward,
+    WordEnd,
+    LineStart,
+    LineEnd,
+    FindForward(char),
+    TillForward(char),
+    FindBackward(char),
+    TillBackward(char),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Delete,
+    Change,
+    Yank,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InsertAt {
+    Cursor,
+    After,
+    Start,
+    End,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ViCommand {
+    Move(Motion),
+    /// An operator without a motion (`dd`, `cc`, `yy`) acts on the line.
+    Operate(Operator, Option<Motion>),
+    DeleteChar,
+    Replace(char),
+    Put {
+        before: bool,
+    },
+    Insert(InsertAt),
+    Undo,
+    Repeat,
+    Action(ViAction),
+}
+
+impl ViCommand {
+    /// Commands that `.` repeats.
+    fn is_change(&self) -> bool {
+        matches!(
+            self,
+            ViCommand::Operate(Operator::Delete | Operator::Change, _)
+                | ViCommand::DeleteChar
+                | ViCommand::Replace(_)
+                | ViCommand::Put { .. }
+                | ViCommand::Insert(_)
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Parsed<T> {
+    Complete(T),
+    Incomplete,
+    Invalid,
+}
+
+/// Last change, with the text typed if it ended in insert mode.
+#[derive(Debug, Clone)]
+struct Change {
+    keys: Vec<char>,
+    inserted: String,
+}
+
+/// Normal mode state of the vi line editor. The register and the last
+/// change are kept from one line to the next.
+#[derive(Debug, Default)]
+pub struct ViEditor {
+    mode: ViMode,
+    pending: Vec<char>,
+    register: String,
+    undo: Vec<LineBuffer>,
+    last_change: Option<Change>,
+    /// Cursor and line length when the last change entered insert mode.
+    insert_start: Option<(usize, usize)>,
+}
+
+impl ViEditor {
+    pub fn mode(&self) -> ViMode {
+        self.mode
+    }
+
+    /// Every line starts in insert mode with nothing to undo.
+    pub fn start_line(&mut self) {
+        self.mode = ViMode::Insert;
+        self.pending.clear();
+        self.undo.clear();
+        self.insert_start = None;
+    }
+
+    /// Leaves insert mode, or drops a half typed command in normal mode.
+    pub fn escape(&mut self, buffer: &mut LineBuffer) {
+        self.pending.clear();
+        if self.mode == ViMode::Normal {
+            return;
+        }
+
+        if let (Some((start, len)), Some(change)) =
+            (self.insert_start.take(), self.last_change.as_mut())
+        {
+            let cursor = buffer.cursor();
+            if cursor >= start && buffer.as_str().len() == len + cursor - start {
+                change.inserted = buffer.as_str()[start..cursor].to_owned();
+            }
+        }
+
+        self.mode = ViMode::Normal;
+        buffer.move_left();
+    }
+
+    pub fn normal(&mut self, buffer: &mut LineBuffer, key: char) -> ViAction {
+        self.pending.push(key);
+        let (count, command) = match parse(&self.pending) {
+            Parsed::Complete(parsed) => parsed,
+            Parsed::Incomplete => return ViAction::Pending,
+            Parsed::Invalid => {
+                self.pending.clear();
+                return ViAction::Bell;
+            }
+        };
+
+        let keys = std::mem::take(&mut self.pending);
+        if command == ViCommand::Repeat {
+            return self.repeat(buffer);
+        }
+
+        let snapshot = buffer.clone();
+        let action = self.execute(buffer, count, command);
+        if command.is_change() && action != ViAction::Bell {
+            self.undo.push(snapshot);
+            self.last_change = Some(Change {
+                keys,
+                inserted: String::new(),
+            });
+            if self.mode == ViMode::Insert {
+                self.insert_start = Some((buffer.cursor(), buffer.as_str().len()));
+            }
+        }
+        action
+    }
+
+    fn repeat(&mut self, buffer: &mut LineBuffer) -> ViAction {
+        let Some(change) = self.last_change.clone() else {
+            return ViAction::Bell;
+        };
+        let Parsed::Complete((count, command)) = parse(&change.keys) else {
+            return ViAction::Bell;
+        };
+
+        self.undo.push(buffer.clone());
+        let action = self.execute(buffer, count, command);
+        if self.mode == ViMode::Insert {
+            buffer.insert_str(&change.inserted);
+            self.mode = ViMode::Normal;
+            buffer.move_left();
+        }
+        action
+    }
+
+    fn execute(&mut self, buffer: &mut LineBuffer, count: usize, command: ViCommand) -> ViAction {
+        let line = Line::new(buffer);
+        let position = line.position(buffer.cursor());
+
+        match command {
+            ViCommand::Move(motion) => match line.motion(position, motion, count) {
+                Some((target, _)) => buffer.move_to(line.offset(target)),
+                None => return ViAction::Bell,
+            },
+            ViCommand::Operate(operator, motion) => {
+                let range = match motion {
+                    None => 0..line.len(),
+                    // `cw` changes up to the end of the word like `ce`
+                    Some(Motion::WordForward)
+                        if operator == Operator::Change && !line.is_blank(position) =>
+                    {
+                        let mut end = line.current_word_end(position);
+                        for _ in 1..count {
+                            end = line.word_end(end);
+                        }
+                        line.span(position, (end, true))
+                    }
+                    Some(motion) => match line.motion(position, motion, count) {
+                        Some(target) => line.span(position, target),
+                        None => return ViAction::Bell,
+                    },
+                };
+                let range = line.offset(range.start)..line.offset(range.end);
+                self.register = buffer.as_str()[range.clone()].to_owned();
+                match operator {
+                    Operator::Yank => buffer.move_to(range.start),
+                    Operator::Delete => buffer.replace(range, ""),
+                    Operator::Change => {
+                        buffer.replace(range, "");
+                        self.mode = ViMode::Insert;
+                        return ViAction::Redraw;
+                    }
+                }
+            }
+            ViCommand::DeleteChar => {
+                if position >= line.len() {
+                    return ViAction::Bell;
+                }
+                let range = line.offset(position)..line.offset((position + count).min(line.len()));
+                self.register = buffer.as_str()[range.clone()].to_owned();
+                buffer.replace(range, "");
+            }
+            ViCommand::Replace(char) => {
+                if position + count > line.len() {
+                    return ViAction::Bell;
+                }
+                let range = line.offset(position)..line.offset(position + count);
+                buffer.replace(range, &char.to_string().repeat(count));
+                buffer.move_left();
+            }
+            ViCommand::Put { before } => {
+                if self.register.is_empty() {
+                    return ViAction::Bell;
+                }
+                if !before {
+                    buffer.move_right();
+                }
+                buffer.insert_str(&self.register.repeat(count));
+                buffer.move_left();
+            }
+            ViCommand::Insert(at) => {
+                match at {
+                    InsertAt::Cursor => {}
+                    InsertAt::After => {
+                        buffer.move_right();
+                    }
+                    InsertAt::Start => buffer.move_home(),
+                    InsertAt::End => buffer.move_end(),
+                }
+                self.mode = ViMode::Insert;
+                return ViAction::Redraw;
+            }
+            ViCommand::Undo => match self.undo.pop() {
+                Some(previous) => *buffer = previous,
+                None => return ViAction::Bell,
+            },
+            ViCommand::Repeat => return self.repeat(buffer),
+            ViCommand::Action(action) => return action,
+        }
+
+        Self::clamp(buffer);
+        ViAction::Redraw
+    }
+
+    /// In normal mode the cursor sits on a character, never past the end.
+    pub fn clamp(buffer: &mut LineBuffer) {
+        if buffer.is_at_end() {
+            buffer.move_left();
+        }
+    }
+}
+
+/// Grapheme view of the line that motions work on. Positions index
+/// graphemes, `offset` maps them back to bytes.
+struct Line<'a> {
+    graphemes: Vec<(usize, &'a str)>,
+    len: usize,
+}
+
+impl<'a> Line<'a> {
+    fn new(buffer: &'a LineBuffer) -> Self {
+        Self {
+            graphemes: buffer.as_str().grapheme_indices(true).collect(),
+            len: buffer.as_str().len(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.graphemes.len()
+    }
+
+    fn position(&self, offset: usize) -> usize {
+        self.graphemes
+            .iter()
+            .position(|&(start, _)| start >= offset)
+            .unwrap_or(self.len())
+    }
+
+    fn offset(&self, position: usize) -> usize {
+        self.graphemes
+            .get(position)
+            .map_or(self.len, |&(start, _)| start)
+    }
+
+    fn is_blank(&self, position: usize) -> bool {
+        self.class(position) == Some(CharClass::Blank)
+    }
+
+    fn class(&self, position: usize) -> Option<CharClass> {
+        let (_, grapheme) = self.graphemes.get(position)?;
+        grapheme.chars().next().map(CharClass::of)
+    }
+
+    fn find(&self, position: usize, char: char) -> bool {
+        self.graphemes
+            .get(position)
+            .is_some_and(|&(_, grapheme)| grapheme.chars().eq([char]))
+    }
+
+    /// Range covered by an operator from `position` to a motion target.
+    fn span(&self, position: usize, (target, inclusive): (usize, bool)) -> Range<usize> {
+        if target >= position {
+            position..(target + usize::from(inclusive)).min(self.len())
+        } else {
+            target..position
+        }
+    }
+
+    /// Target of `motion` repeated `count` times and whether it is
+    /// inclusive, `None` when it can't move.
+    fn motion(&self, position: usize, motion: Motion, count: usize) -> Option<(usize, bool)> {
+        let len = self.len();
+        let last = len.saturating_sub(1);
+
+        let target = match motion {
+            Motion::Left if position > 0 => (position.saturating_sub(count), false),
+            Motion::Right if position < len => ((position + count).min(len), false),
+            Motion::Left | Motion::Right => return None,
+            Motion::LineStart => (0, false),
+            Motion::LineEnd => (last, true),
+            Motion::WordForward => {
+                let mut target = position;
+                for _ in 0..count {
+                    target = self.next_word_start(target);
+                }
+                (target, false)
+            }
+            Motion::WordBackward => {
+                let mut target = position;
+                for _ in 0..count {
+                    target = self.previous_word_start(target);
+                }
+                (target, false)
+            }
+            Motion::WordEnd => {
+                let mut target = position;
+                for _ in 0..count {
+                    target = self.word_end(target);
+                }
+                (target.min(last), true)
+            }
+            Motion::FindForward(char) | Motion::TillForward(char) => {
+                let mut target = position;
+                for _ in 0..count {
+                    target = (target + 1..len).find(|&idx| self.find(idx, char))?;
+                }
+                match motion {
+                    Motion::TillForward(_) => (target - 1, true),
+                    _ => (target, true),
+                }
+            }
+            Motion::FindBackward(char) | Motion::TillBackward(char) => {
+                let mut target = position;
+                for _ in 0..count {
+                    target = (0..target).rev().find(|&idx| self.find(idx, char))?;
+                }
+                match motion {
+                    Motion::TillBackward(_) => (target + 1, false),
+                    _ => (target, false),
+                }
+            }
+        };
+        Some(target)
+    }
+
+    fn next_word_start(&self, mut position: usize) -> usize {
+        let Some(class) = self.class(position) else {
+            return position;
+        };
+        if class != CharClass::Blank {
+            while self.class(position) == Some(class) {
+                position += 1;
+            }
+        }
+        while self.is_blank(position) {
+            position += 1;
+        }
+        position
+    }
+
+    fn previous_word_start(&self, mut position: usize) -> usize {
+        while position > 0 && self.is_blank(position - 1) {
+            position -= 1;
+        }
+        let Some(class) = position.checked_sub(1).and_then(|idx| self.class(idx)) else {
+            return position;
+        };
+        while position > 0 && self.class(position - 1) == Some(class) {
+            position -= 1;
+        }
+        position
+    }
+
+    fn current_word_end(&self, mut position: usize) -> usize {
+        let class = self.class(position);
+        while class.is_some() && self.class(position + 1) == class {
+            position += 1;
+        }
+        position
+    }
+
+    fn word_end(&self, mut position: usize) -> usize {
+        position += 1;
+        while self.is_blank(position) {
+            position += 1;
+        }
+        let Some(class) = self.class(position) else {
+            return position;
+        };
+        while self.class(position + 1) == Some(class) {
+            position += 1;
+        }
+        position
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Blank,
+    Word,
+    Punctuation,
+}
+
+impl CharClass {
+    fn of(char: char) -> Self {
+        if char.is_whitespace() {
+            CharClass::Blank
+        } else if char.is_alphanumeric() || char == '_' {
+            CharClass::Word
+        } else {
+            CharClass::Punctuation
+        }
+    }
+}
+
+/// Splits a leading count, `0` on its own is the line start motion.
+fn split_count(keys: &[char]) -> (Option<usize>, &[char]) {
+    let digits = keys
+        .iter()
+        .enumerate()
+        .take_while(|&(idx, char)| char.is_ascii_digit() && (idx > 0 || *char != '0'))
+        .count();
+    let count = keys[..digits].iter().collect::<String>().parse().ok();
+    (count, &keys[digits..])
+}
+
+fn parse_motion(keys: &[char]) -> Parsed<Motion> {
+    let motion = match keys {
+        [] => return Parsed::Incomplete,
+        ['h'] => Motion::Left,
+        ['l' | ' '] => Motion::Right,
+        ['w'] => Motion::WordForward,
+        ['b'] => Motion::WordBackward,
+        ['e'] => Motion::WordEnd,
+        ['0' | '^'] => Motion::LineStart,
+        ['$'] => Motion::LineEnd,
+        ['f' | 't' | 'F' | 'T'] => return Parsed::Incomplete,
+        ['f', char] => Motion::FindForward(*char),
+        ['t', char] => Motion::TillForward(*char),
+        ['F', char] => Motion::FindBackward(*char),
+        ['T', char] => Motion::TillBackward(*char),
+        _ => return Parsed::Invalid,
+    };
+    Parsed::Complete(motion)
+}
+
+/// Parses `[count] command`, where an operator takes `[count] motion` or
+/// its own key again.
+fn parse(keys: &[char]) -> Parsed<(usize, ViCommand)> {
+    let (count, keys) = split_count(keys);
+    let mut count = count.unwrap_or(1);
+
+    let command = match keys {
+        [] => return Parsed::Incomplete,
+        [operator @ ('d' | 'c' | 'y'), rest @ ..] => {
+            let operator_kind = match operator {
+                'd' => Operator::Delete,
+                'c' => Operator::Change,
+                _ => Operator::Yank,
+            };
+            let (motion_count, rest) = split_count(rest);
+            count *= motion_count.unwrap_or(1);
+            match rest {
+                [key] if key == operator => ViCommand::Operate(operator_kind, None),
+                rest => match parse_motion(rest) {
+                    Parsed::Complete(motion) => ViCommand::Operate(operator_kind, Some(motion)),
+                    Parsed::Incomplete => return Parsed::Incomplete,
+                    Parsed::Invalid => return Parsed::Invalid,
+                },
+            }
+        }
+        ['D'] => ViCommand::Operate(Operator::Delete, Some(Motion::LineEnd)),
+        ['C'] => ViCommand::Operate(Operator::Change, Some(Motion::LineEnd)),
+        ['x'] => ViCommand::DeleteChar,
+        ['r'] => return Parsed::Incomplete,
+        ['r', char] => ViCommand::Replace(*char),
+        ['p'] => ViCommand::Put { before: false },
+        ['P'] => ViCommand::Put { before: true },
+        ['i'] => ViCommand::Insert(InsertAt::Cursor),
+        ['a'] => ViCommand::Insert(InsertAt::After),
+        ['I'] => ViCommand::Insert(InsertAt::Start),
+        ['A'] => ViCommand::Insert(InsertAt::End),
+        ['u'] => ViCommand::Undo,
+        ['.'] => ViCommand::Repeat,
+        ['k' | '-'] => ViCommand::Action(ViAction::HistoryPrevious),
+        ['j' | '+'] => ViCommand::Action(ViAction::HistoryNext),
+        ['/'] => ViCommand::Action(ViAction::Search(SearchDirection::Backward)),
+        ['?'] => ViCommand::Action(ViAction::Search(SearchDirection::Forward)),
+        ['n'] => ViCommand::Action(ViAction::SearchAgain { reverse: false }),
+        ['N'] => ViCommand::Action(ViAction::SearchAgain { reverse: true }),
+        ['v'] => ViCommand::Action(ViAction::Edit),
+        keys => match parse_motion(keys) {
+            Parsed::Complete(motion) => ViCommand::Move(motion),
+            Parsed::Incomplete => return Parsed::Incomplete,
+            Parsed::Invalid => return Parsed::Invalid,
+        },
+    };
+    Parsed::Complete((count, command))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs `keys` in normal mode on `text` with the cursor on its last
+    /// character, `<` stands for escape.
+    fn run(text: &str, keys: &str) -> (ViEditor, LineBuffer) {
+        let mut editor = ViEditor::default();
+        let mut buffer = LineBuffer::new(text);
+        editor.escape(&mut buffer);
+        for key in keys.chars() {
+            match (key, editor.mode()) {
+                ('<', _) => editor.escape(&mut buffer),
+                (key, ViMode::Normal) => {
+                    editor.normal(&mut buffer, key);
+                }
+                (key, ViMode::Insert) => buffer.insert(key),
+            }
+        }
+        (editor, buffer)
+    }
+
+    fn line(text: &str, keys: &str) -> String {
+        run(text, keys).1.into_string()
+    }
+
+    fn cursor(text: &str, keys: &str) -> usize {
+        run(text, keys).1.cursor()
+    }
+
+    #[test]
+    fn motions() {
+        assert_eq!(cursor("echo one two", "0w"), 5);
+        assert_eq!(cursor("echo one two", "02w"), 9);
+        assert_eq!(cursor("echo one two", "b"), 9);
+        assert_eq!(cursor("echo one two", "0e"), 3);
+        assert_eq!(cursor("echo one two", "0$"), 11);
+        assert_eq!(cursor("echo one two", "0fo"), 3);
+        assert_eq!(cursor("echo one two", "0to"), 2);
+        assert_eq!(cursor("echo one two", "0l3h"), 0);
+        assert_eq!(cursor("a-b c", "0w"), 1);
+    }
+
+    #[test]
+    fn operators_with_motions() {
+        assert_eq!(line("echo one two", "0dw"), "one two");
+        assert_eq!(line("echo one two", "0d2w"), "two");
+        assert_eq!(line("echo one two", "0wd$"), "echo ");
+        assert_eq!(line("echo one two", "0dfn"), "e two");
+        assert_eq!(line("echo one two", "0wcwthree<"), "echo three two");
+        assert_eq!(line("a b", "0cwc<"), "c b");
+        assert_eq!(line("echo one two", "dd"), "");
+        assert_eq!(line("echo one two", "0ywP"), "echo echo one two");
+    }
+
+    #[test]
+    fn delete_and_replace_characters() {
+        assert_eq!(line("echo", "0x"), "cho");
+        assert_eq!(line("echo", "02x"), "ho");
+        assert_eq!(line("echo", "0rE"), "Echo");
+        assert_eq!(line("abc", "0xp"), "bac");
+    }
+
+    #[test]
+    fn insert_commands() {
+        assert_eq!(line("cho", "0ie<"), "echo");
+        assert_eq!(line("ech", "ao<"), "echo");
+        assert_eq!(line("ls", "Ae<A!<"), "lse!");
+        assert_eq!(line("ls", "$I-<"), "-ls");
+    }
+
+    #[test]
+    fn undo_restores_previous_line() {
+        assert_eq!(line("echo one", "0dwu"), "echo one");
+        assert_eq!(line("echo", "Aab<uu"), "echo");
+    }
+
+    #[test]
+    fn repeat_last_change() {
+        assert_eq!(line("a b c d", "0dw.."), "d");
+        assert_eq!(line("one two", "0cwx<w."), "x x");
+        assert_eq!(line("abc", "0x."), "c");
+    }
+
+    #[test]
+    fn invalid_and_pending_keys() {
+        let mut editor = ViEditor::default();
+        let mut buffer = LineBuffer::new("echo");
+        editor.escape(&mut buffer);
+        assert_eq!(editor.normal(&mut buffer, 'd'), ViAction::Pending);
+        assert_eq!(editor.normal(&mut buffer, 'z'), ViAction::Bell);
+        assert_eq!(editor.normal(&mut buffer, 'k'), ViAction::HistoryPrevious);
+        assert_eq!(editor.normal(&mut buffer, 'f'), ViAction::Pending);
+        assert_eq!(editor.normal(&mut buffer, 'q'), ViAction::Bell);
+    }
+}
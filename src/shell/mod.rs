@@ -1,6 +1,12 @@
+pub mod alias;
 pub mod completion;
 pub mod file;
+pub mod history;
 pub mod input;
+pub mod options;
 pub mod output_handler;
+pub mod parameters;
 pub mod path;
+pub mod prompt;
 pub mod raw_mode;
+pub mod terminal;
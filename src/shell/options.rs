@@ -0,0 +1,186 @@
+use std::{collections::HashSet, fmt::Display, str::FromStr, sync::RwLock};
+
+use crate::exceptions::commands::ShellError;
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, strum::EnumIter)]
+pub enum ShellOption {
+    Errexit,
+    Xtrace,
+    Interactive,
+    Login,
+    NoRc,
+    HistExpand,
+    IgnoreEof,
+    Emacs,
+    Vi,
+    CompIgnoreCase,
+    CompSubstring,
+    CompFuzzy,
+}
+
+impl ShellOption {
+    pub fn from_flag(flag: char) -> Option<Self> {
+        match flag {
+            'e' => Some(Self::Errexit),
+            'x' => Some(Self::Xtrace),
+            'i' => Some(Self::Interactive),
+            'l' => Some(Self::Login),
+            'H' => Some(Self::HistExpand),
+            _ => None,
+        }
+    }
+
+    /// Attributes of how the shell was started, which `set` can't change.
+    pub fn is_startup_only(&self) -> bool {
+        matches!(self, Self::Interactive | Self::Login | Self::NoRc)
+    }
+
+    /// Options that are switched off when this one is enabled.
+    fn excludes(&self) -> &'static [Self] {
+        match self {
+            Self::Emacs => &[Self::Vi],
+            Self::Vi => &[Self::Emacs],
+            Self::CompIgnoreCase => &[Self::CompSubstring, Self::CompFuzzy],
+            Self::CompSubstring => &[Self::CompIgnoreCase, Self::CompFuzzy],
+            Self::CompFuzzy => &[Self::CompIgnoreCase, Self::CompSubstring],
+            _ => &[],
+        }
+    }
+}
+
+impl FromStr for ShellOption {
+    type Err = ShellError;
+    fn from_str(option: &str) -> Result<Self, Self::Err> {
+        match option {
+            "errexit" => Ok(Self::Errexit),
+            "xtrace" => Ok(Self::Xtrace),
+            "interactive" => Ok(Self::Interactive),
+            "login" => Ok(Self::Login),
+            "norc" => Ok(Self::NoRc),
+            "histexpand" => Ok(Self::HistExpand),
+            "ignoreeof" => Ok(Self::IgnoreEof),
+            "emacs" => Ok(Self::Emacs),
+            "vi" => Ok(Self::Vi),
+            "compignorecase" => Ok(Self::CompIgnoreCase),
+            "compsubstring" => Ok(Self::CompSubstring),
+            "compfuzzy" => Ok(Self::CompFuzzy),
+            _ => Err(ShellError::InvalidOption(option.to_owned())),
+        }
+    }
+}
+
+impl Display for ShellOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let option = match self {
+            ShellOption::Errexit => "errexit",
+            ShellOption::Xtrace => "xtrace",
+            ShellOption::Interactive => "interactive",
+            ShellOption::Login => "login",
+            ShellOption::NoRc => "norc",
+            ShellOption::HistExpand => "histexpand",
+            ShellOption::IgnoreEof => "ignoreeof",
+            ShellOption::Emacs => "emacs",
+            ShellOption::Vi => "vi",
+            ShellOption::CompIgnoreCase => "compignorecase",
+            ShellOption::CompSubstring => "compsubstring",
+            ShellOption::CompFuzzy => "compfuzzy",
+        };
+        f.pad(option)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ShellOptions {
+    enabled: RwLock<HashSet<ShellOption>>,
+}
+
+impl ShellOptions {
+    pub fn new(options: impl IntoIterator<Item = ShellOption>) -> Self {
+        Self {
+            enabled: RwLock::new(options.into_iter().collect()),
+        }
+    }
+
+    pub fn is_enabled(&self, option: ShellOption) -> bool {
+        self.enabled
+            .read()
+            .map(|enabled| enabled.contains(&option))
+            .unwrap_or(false)
+    }
+
+    pub fn enable(&self, option: ShellOption) {
+        if let Ok(mut enabled) = self.enabled.write() {
+            for excluded in option.excludes() {
+                enabled.remove(excluded);
+            }
+            enabled.insert(option);
+        }
+    }
+
+    pub fn disable(&self, option: ShellOption) {
+        if let Ok(mut enabled) = self.enabled.write() {
+            enabled.remove(&option);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_flags_map_to_options() {
+        assert_eq!(ShellOption::from_flag('e'), Some(ShellOption::Errexit));
+        assert_eq!(ShellOption::from_flag('x'), Some(ShellOption::Xtrace));
+        assert_eq!(ShellOption::from_flag('i'), Some(ShellOption::Interactive));
+        assert_eq!(ShellOption::from_flag('l'), Some(ShellOption::Login));
+        assert_eq!(ShellOption::from_flag('H'), Some(ShellOption::HistExpand));
+        assert_eq!(ShellOption::from_flag('z'), None);
+    }
+
+    #[test]
+    fn long_names_round_trip() {
+        for option in <ShellOption as strum::IntoEnumIterator>::iter() {
+            assert_eq!(option.to_string().parse::<ShellOption>(), Ok(option));
+        }
+    }
+
+    #[test]
+    fn unknown_long_name_is_an_error() {
+        assert_eq!(
+            "nope".parse::<ShellOption>(),
+            Err(ShellError::InvalidOption("nope".to_owned()))
+        );
+    }
+
+    #[test]
+    fn enable_and_disable() {
+        let options = ShellOptions::new([ShellOption::Xtrace]);
+        assert!(options.is_enabled(ShellOption::Xtrace));
+        assert!(!options.is_enabled(ShellOption::Errexit));
+
+        options.enable(ShellOption::Errexit);
+        options.disable(ShellOption::Xtrace);
+        assert!(options.is_enabled(ShellOption::Errexit));
+        assert!(!options.is_enabled(ShellOption::Xtrace));
+    }
+
+    #[test]
+    fn editing_modes_are_exclusive() {
+        let options = ShellOptions::new([ShellOption::Emacs]);
+        options.enable(ShellOption::Vi);
+        assert!(options.is_enabled(ShellOption::Vi));
+        assert!(!options.is_enabled(ShellOption::Emacs));
+
+        options.enable(ShellOption::Emacs);
+        assert!(!options.is_enabled(ShellOption::Vi));
+    }
+
+    #[test]
+    fn completion_matchings_are_exclusive() {
+        let options = ShellOptions::new([ShellOption::CompIgnoreCase]);
+        options.enable(ShellOption::CompFuzzy);
+        assert!(options.is_enabled(ShellOption::CompFuzzy));
+        assert!(!options.is_enabled(ShellOption::CompIgnoreCase));
+    }
+}
@@ -22,13 +22,13 @@ impl OutputHandler {
     ) -> Result<(), ShellError> {
         match command_result {
             CommandResult::Exit(code) => std::process::exit(code),
-            CommandResult::Stdio(stdout, stderr) => {
+            CommandResult::Stdio(stdout, stderr) | CommandResult::Failure(stdout, stderr, _) => {
                 self.write_output(&stdout, &stderr, redirection)
             }
             CommandResult::Error(error) => {
                 self.write_output("", &format!("{}\n", &error.to_string()), redirection)
             }
-            CommandResult::Empty => Ok(()),
+            CommandResult::Source(_, _) | CommandResult::Return(_) | CommandResult::Empty => Ok(()),
         }
     }
 
@@ -0,0 +1,50 @@
+use std::sync::{
+    atomic::{AtomicI32, Ordering},
+    RwLock,
+};
+
+#[derive(Debug, Default)]
+pub struct ShellParameters {
+    name: RwLock<String>,
+    positional: RwLock<Vec<String>>,
+    last_status: AtomicI32,
+}
+
+impl ShellParameters {
+    pub fn new(name: impl Into<String>, positional: Vec<String>) -> Self {
+        Self {
+            name: RwLock::new(name.into()),
+            positional: RwLock::new(positional),
+            last_status: AtomicI32::new(0),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        self.name
+            .read()
+            .map(|name| name.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn positional(&self) -> Vec<String> {
+        self.positional
+            .read()
+            .map(|positional| positional.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn replace_positional(&self, positional: Vec<String>) -> Vec<String> {
+        match self.positional.write() {
+            Ok(mut current) => std::mem::replace(&mut *current, positional),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    pub fn last_status(&self) -> i32 {
+        self.last_status.load(Ordering::Relaxed)
+    }
+
+    pub fn set_last_status(&self, status: i32) {
+        self.last_status.store(status, Ordering::Relaxed)
+    }
+}
@@ -1,23 +1,118 @@
-use std::{fs::ReadDir, os::unix::fs::PermissionsExt, path::PathBuf, vec};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    ffi::{OsStr, OsString},
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::SystemTime,
+};
+
+/// A command remembered by `hash`, with the number of times it was run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashedCommand {
+    pub path: PathBuf,
+    pub hits: usize,
+}
 
-use crate::shell::completion::path_dirs;
+/// The executables of a PATH directory as of its last modification.
+struct DirIndex {
+    modified: SystemTime,
+    executables: Arc<BTreeSet<String>>,
+}
 
+/// The directories of PATH as of its last value.
+struct PathDirs {
+    value: Option<OsString>,
+    dirs: Arc<Vec<PathBuf>>,
+}
+
+/// Looks commands up in the PATH directories. Directory listings are kept
+/// until a directory changes, and commands that were run are hashed by
+/// name. A provider made from the environment follows changes to PATH.
 pub struct PathDirsProvider {
-    path_dirs: Vec<PathBuf>,
+    variable: Option<&'static str>,
+    path_dirs: RwLock<PathDirs>,
+    index: RwLock<HashMap<PathBuf, DirIndex>>,
+    hashed: RwLock<BTreeMap<String, HashedCommand>>,
 }
 
 impl PathDirsProvider {
     pub fn from_env() -> Self {
-        let paths = std::env::var("PATH").unwrap_or("".to_owned());
-        let paths: Vec<PathBuf> = std::env::split_paths(&paths).collect();
-        Self { path_dirs: paths }
+        Self::from_var("PATH")
+    }
+
+    /// Follows the directories listed in the environment `variable`.
+    pub fn from_var(variable: &'static str) -> Self {
+        let value = std::env::var_os(variable);
+        Self {
+            variable: Some(variable),
+            path_dirs: RwLock::new(PathDirs {
+                dirs: Arc::new(Self::split(value.as_deref())),
+                value,
+            }),
+            index: RwLock::new(HashMap::new()),
+            hashed: RwLock::new(BTreeMap::new()),
+        }
     }
 
     pub fn new(path_dirs: Vec<PathBuf>) -> Self {
-        Self { path_dirs }
+        Self {
+            variable: None,
+            path_dirs: RwLock::new(PathDirs {
+                value: None,
+                dirs: Arc::new(path_dirs),
+            }),
+            index: RwLock::new(HashMap::new()),
+            hashed: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// Empty entries stand for the current directory.
+    fn split(value: Option<&OsStr>) -> Vec<PathBuf> {
+        let Some(value) = value else {
+            return Vec::new();
+        };
+        std::env::split_paths(value)
+            .map(|dir| {
+                if dir.as_os_str().is_empty() {
+                    PathBuf::from(".")
+                } else {
+                    dir
+                }
+            })
+            .collect()
     }
 
-    pub fn is_executable(&self, exe_path: &PathBuf) -> bool {
+    /// The directories to search, split again when the variable changed
+    /// since the last lookup. Commands hashed from the old value are
+    /// forgotten, as in bash.
+    pub fn dirs(&self) -> Arc<Vec<PathBuf>> {
+        let Some(variable) = self.variable else {
+            return self
+                .path_dirs
+                .read()
+                .map(|path_dirs| Arc::clone(&path_dirs.dirs))
+                .unwrap_or_default();
+        };
+        let value = std::env::var_os(variable);
+        if let Ok(path_dirs) = self.path_dirs.read() {
+            if path_dirs.value == value {
+                return Arc::clone(&path_dirs.dirs);
+            }
+        }
+
+        let dirs = Arc::new(Self::split(value.as_deref()));
+        if let Ok(mut path_dirs) = self.path_dirs.write() {
+            *path_dirs = PathDirs {
+                value,
+                dirs: Arc::clone(&dirs),
+            };
+        }
+        self.clear_hashed();
+        dirs
+    }
+
+    pub fn is_executable(&self, exe_path: &Path) -> bool {
         if !exe_path.is_file() {
             return false;
         }
@@ -29,20 +124,133 @@ impl PathDirsProvider {
 
         false
     }
-    pub fn find_executable(&self, exe_name: &str) -> Option<PathBuf> {
-        self.path_dirs.iter().find_map(|path_dir| {
-            let exe_path = path_dir.join(exe_name);
 
-            if self.is_executable(&exe_path) {
-                return Some(exe_path);
+    /// The executables of `dir`, listed again only when its mtime changed.
+    /// The listing is for completion, commands are searched file by file.
+    /// Relative directories are indexed by where they are now.
+    fn dir_executables(&self, dir: &Path) -> Option<Arc<BTreeSet<String>>> {
+        let dir = &std::env::current_dir().ok()?.join(dir);
+        let modified = dir.metadata().and_then(|meta| meta.modified()).ok()?;
+        if let Some(index) = self.index.read().ok()?.get(dir) {
+            if index.modified == modified {
+                return Some(Arc::clone(&index.executables));
             }
+        }
+
+        let executables: BTreeSet<String> = std::fs::read_dir(dir)
+            .ok()?
+            .flatten()
+            .filter(|entry| self.is_executable(&entry.path()))
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect();
+        let executables = Arc::new(executables);
+        if let Ok(mut index) = self.index.write() {
+            index.insert(
+                dir.to_path_buf(),
+                DirIndex {
+                    modified,
+                    executables: Arc::clone(&executables),
+                },
+            );
+        }
+        Some(executables)
+    }
+
+    /// The names of all executables in PATH, sorted.
+    pub fn executables(&self) -> Vec<String> {
+        let names: BTreeSet<String> = self
+            .dirs()
+            .iter()
+            .filter_map(|path_dir| self.dir_executables(path_dir))
+            .flat_map(|executables| executables.iter().cloned().collect::<Vec<_>>())
+            .collect();
+        names.into_iter().collect()
+    }
+
+    /// Searches PATH, skipping the hash table. Files are checked as they
+    /// are now, a `chmod` doesn't change the listing of their directory.
+    fn search(&self, exe_name: &str) -> Option<PathBuf> {
+        self.dirs()
+            .iter()
+            .map(|path_dir| path_dir.join(exe_name))
+            .find(|exe_path| self.is_executable(exe_path))
+    }
 
-            None
-        })
+    /// The hashed path of `exe_name` while it's still executable, or the
+    /// first one in PATH.
+    pub fn find_executable(&self, exe_name: &str) -> Option<PathBuf> {
+        self.hashed(exe_name)
+            .map(|hashed| hashed.path)
+            .filter(|path| self.is_executable(path))
+            .or_else(|| self.search(exe_name))
+    }
+
+    /// Finds `exe_name` to run it, hashing it and counting the hit.
+    pub fn lookup(&self, exe_name: &str) -> Option<PathBuf> {
+        let path = self.find_executable(exe_name)?;
+        if let Ok(mut hashed) = self.hashed.write() {
+            let hits = match hashed.get(exe_name) {
+                Some(entry) if entry.path == path => entry.hits,
+                _ => 0,
+            };
+            hashed.insert(
+                exe_name.to_owned(),
+                HashedCommand {
+                    path: path.clone(),
+                    hits: hits + 1,
+                },
+            );
+        }
+        Some(path)
     }
 
-    pub fn iter(&self) -> std::slice::Iter<'_, PathBuf> {
-        self.path_dirs.iter()
+    /// Searches PATH for `exe_name` again and hashes it, as `hash name`.
+    pub fn hash(&self, exe_name: &str) -> Option<PathBuf> {
+        let path = self.search(exe_name)?;
+        self.set_hashed(exe_name, path.clone());
+        Some(path)
+    }
+
+    pub fn set_hashed(&self, exe_name: &str, path: PathBuf) {
+        if let Ok(mut hashed) = self.hashed.write() {
+            hashed.insert(exe_name.to_owned(), HashedCommand { path, hits: 0 });
+        }
+    }
+
+    pub fn hashed(&self, exe_name: &str) -> Option<HashedCommand> {
+        // A changed PATH empties the table first
+        self.dirs();
+        self.hashed
+            .read()
+            .ok()
+            .and_then(|hashed| hashed.get(exe_name).cloned())
+    }
+
+    pub fn clear_hashed(&self) {
+        if let Ok(mut hashed) = self.hashed.write() {
+            hashed.clear();
+        }
+    }
+
+    /// All hashed commands sorted by name.
+    pub fn hashed_commands(&self) -> Vec<(String, HashedCommand)> {
+        self.dirs();
+        self.hashed
+            .read()
+            .map(|hashed| {
+                hashed
+                    .iter()
+                    .map(|(name, command)| (name.clone(), command.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn find_file(&self, file_name: &str) -> Option<PathBuf> {
+        self.dirs()
+            .iter()
+            .map(|path_dir| path_dir.join(file_name))
+            .find(|file_path| file_path.is_file())
     }
 }
 
@@ -57,20 +265,20 @@ mod tests {
         let dirs = vec![PathBuf::from("/usr/bin"), PathBuf::from("/bin")];
         let path = PathDirsProvider::new(dirs.clone());
 
-        assert_eq!(path.path_dirs.len(), 2);
+        assert_eq!(path.dirs().len(), 2);
     }
 
     #[test]
     fn new_creates_empty_path() {
         let path = PathDirsProvider::new(vec![]);
-        assert_eq!(path.path_dirs.len(), 0);
+        assert_eq!(path.dirs().len(), 0);
     }
 
     #[test]
     fn from_env_creates_path_from_environment() {
         let path = PathDirsProvider::from_env();
-        // PATH typically has at least one directory
-        assert!(path.path_dirs.len() >= 0);
+        let paths = std::env::var("PATH").unwrap_or_default();
+        assert_eq!(path.dirs().len(), std::env::split_paths(&paths).count());
     }
 
     // find_executable tests with system executables
@@ -196,4 +404,139 @@ mod tests {
             "LS should not exist (case-sensitive)"
         );
     }
+
+    // Hash table tests
+
+    fn temp_path_dir(names: &[&str]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for name in names {
+            let path = dir.path().join(name);
+            std::fs::write(&path, "#!/bin/sh\n").unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn executables_are_listed_again_when_the_directory_changes() {
+        let dir = temp_path_dir(&["alpha"]);
+        std::fs::write(dir.path().join("notes"), "").unwrap();
+        let path = PathDirsProvider::new(vec![dir.path().to_path_buf()]);
+        assert_eq!(path.executables(), vec!["alpha".to_string()]);
+
+        let beta = dir.path().join("beta");
+        std::fs::write(&beta, "").unwrap();
+        std::fs::set_permissions(&beta, std::fs::Permissions::from_mode(0o755)).unwrap();
+        // Coarse mtimes may not tell the two listings apart
+        let later = SystemTime::now() + std::time::Duration::from_secs(5);
+        std::fs::File::open(dir.path())
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        assert_eq!(
+            path.executables(),
+            vec!["alpha".to_string(), "beta".to_string()]
+        );
+        assert_eq!(path.find_executable("beta"), Some(beta));
+    }
+
+    #[test]
+    fn chmod_after_listing_makes_a_file_executable() {
+        let dir = temp_path_dir(&["alpha"]);
+        let script = dir.path().join("script");
+        std::fs::write(&script, "#!/bin/sh\n").unwrap();
+        let path = PathDirsProvider::new(vec![dir.path().to_path_buf()]);
+        assert_eq!(path.executables(), vec!["alpha".to_string()]);
+        assert_eq!(path.find_executable("script"), None);
+
+        // The directory's mtime stays the same
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert_eq!(path.find_executable("script"), Some(script.clone()));
+        assert_eq!(path.lookup("script"), Some(script));
+    }
+
+    #[test]
+    fn lookup_hashes_and_counts_hits() {
+        let dir = temp_path_dir(&["alpha"]);
+        let path = PathDirsProvider::new(vec![dir.path().to_path_buf()]);
+        assert_eq!(path.hashed("alpha"), None);
+
+        path.lookup("alpha");
+        path.lookup("alpha");
+        assert_eq!(
+            path.hashed("alpha"),
+            Some(HashedCommand {
+                path: dir.path().join("alpha"),
+                hits: 2
+            })
+        );
+        assert_eq!(path.lookup("missing"), None);
+        assert_eq!(path.hashed_commands().len(), 1);
+
+        path.clear_hashed();
+        assert_eq!(path.hashed("alpha"), None);
+    }
+
+    #[test]
+    fn hashed_path_is_used_while_executable() {
+        let first = temp_path_dir(&["alpha"]);
+        let second = temp_path_dir(&["alpha"]);
+        let path = PathDirsProvider::new(vec![first.path().to_path_buf()]);
+
+        path.set_hashed("alpha", second.path().join("alpha"));
+        assert_eq!(
+            path.find_executable("alpha"),
+            Some(second.path().join("alpha"))
+        );
+
+        path.set_hashed("alpha", PathBuf::from("/does/not/exist"));
+        assert_eq!(
+            path.find_executable("alpha"),
+            Some(first.path().join("alpha"))
+        );
+    }
+
+    // Dynamic PATH tests
+
+    #[test]
+    fn dirs_follow_the_variable() {
+        let first = temp_path_dir(&["alpha"]);
+        let second = temp_path_dir(&["beta"]);
+        std::env::set_var("PATH_DIRS_TEST_FOLLOW", first.path());
+        let path = PathDirsProvider::from_var("PATH_DIRS_TEST_FOLLOW");
+        path.lookup("alpha");
+        assert!(path.hashed("alpha").is_some());
+
+        std::env::set_var(
+            "PATH_DIRS_TEST_FOLLOW",
+            std::env::join_paths([second.path(), first.path()]).unwrap(),
+        );
+        assert_eq!(
+            path.dirs().as_slice(),
+            [second.path().to_path_buf(), first.path().to_path_buf()]
+        );
+        assert_eq!(path.hashed("alpha"), None);
+        assert_eq!(
+            path.executables(),
+            vec!["alpha".to_string(), "beta".to_string()]
+        );
+
+        std::env::remove_var("PATH_DIRS_TEST_FOLLOW");
+        assert!(path.dirs().is_empty());
+    }
+
+    #[test]
+    fn empty_entries_are_the_current_directory() {
+        std::env::set_var("PATH_DIRS_TEST_EMPTY", "/usr/bin::/bin:");
+        let path = PathDirsProvider::from_var("PATH_DIRS_TEST_EMPTY");
+        assert_eq!(
+            path.dirs().as_slice(),
+            [
+                PathBuf::from("/usr/bin"),
+                PathBuf::from("."),
+                PathBuf::from("/bin"),
+                PathBuf::from("."),
+            ]
+        );
+    }
 }
@@ -0,0 +1,260 @@
+use std::{ffi::CStr, iter::Peekable, path::Path, str::Chars};
+
+use crate::shell::input::commons::{IGNORE_END, IGNORE_START};
+
+const DAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// What the escapes of a prompt stand for, besides the environment.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PromptState {
+    /// The history number of the command about to be typed.
+    pub history_number: usize,
+    pub last_status: i32,
+}
+
+/// Expands the bash escapes of `prompt`, then `$NAME`, `${NAME}`, `$?` and
+/// `$(command)`, whose output `substitute` returns. The text of escapes is
+/// never expanded again. `\[` and `\]` become `IGNORE_START` and
+/// `IGNORE_END` for the renderer to leave out of the width.
+pub fn expand(prompt: &str, state: &PromptState, substitute: impl Fn(&str) -> String) -> String {
+    let mut expanded = String::new();
+    let mut chars = prompt.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        match char {
+            '\\' => match chars.next() {
+                Some(escape) => expanded.push_str(&escape_text(escape, &mut chars, state)),
+                None => expanded.push('\\'),
+            },
+            '$' => expanded.push_str(&parameter(&mut chars, state, &substitute)),
+            _ => expanded.push(char),
+        }
+    }
+    expanded
+}
+
+fn escape_text(escape: char, chars: &mut Peekable<Chars>, state: &PromptState) -> String {
+    match escape {
+        'u' => user(),
+        'h' => hostname().split('.').next().unwrap_or_default().to_owned(),
+        'H' => hostname(),
+        'w' => working_dir(false),
+        'W' => working_dir(true),
+        '$' if unsafe { libc::geteuid() } == 0 => "#".to_owned(),
+        '$' => "$".to_owned(),
+        't' => local_time().map_or_else(String::new, |tm| {
+            format!("{:02}:{:02}:{:02}", tm.tm_hour, tm.tm_min, tm.tm_sec)
+        }),
+        'A' => local_time().map_or_else(String::new, |tm| {
+            format!("{:02}:{:02}", tm.tm_hour, tm.tm_min)
+        }),
+        'd' => local_time().map_or_else(String::new, |tm| {
+            format!(
+                "{} {} {:02}",
+                DAYS[tm.tm_wday as usize % 7],
+                MONTHS[tm.tm_mon as usize % 12],
+                tm.tm_mday
+            )
+        }),
+        'n' => "\n".to_owned(),
+        'r' => "\r".to_owned(),
+        'a' => "\x07".to_owned(),
+        'e' => "\x1b".to_owned(),
+        // There is no job control, so no jobs
+        'j' => "0".to_owned(),
+        '!' => state.history_number.to_string(),
+        '?' => state.last_status.to_string(),
+        '[' => IGNORE_START.to_string(),
+        ']' => IGNORE_END.to_string(),
+        '\\' => "\\".to_owned(),
+        '0'..='7' => {
+            let mut code = escape.to_digit(8).unwrap_or_default();
+            for _ in 0..2 {
+                match chars.peek().and_then(|digit| digit.to_digit(8)) {
+                    Some(digit) => {
+                        code = code * 8 + digit;
+                        chars.next();
+                    }
+                    None => break,
+                }
+            }
+            char::from_u32(code).map(String::from).unwrap_or_default()
+        }
+        _ => format!("\\{escape}"),
+    }
+}
+
+/// Expands what follows a `$`, which is kept when nothing does. A `${` or
+/// `$(` that isn't closed is kept as is.
+fn parameter(
+    chars: &mut Peekable<Chars>,
+    state: &PromptState,
+    substitute: &impl Fn(&str) -> String,
+) -> String {
+    match chars.peek() {
+        Some('?') => {
+            chars.next();
+            state.last_status.to_string()
+        }
+        Some('{') => {
+            chars.next();
+            let mut name = String::new();
+            loop {
+                match chars.next() {
+                    Some('}') => return std::env::var(name).unwrap_or_default(),
+                    Some(char) => name.push(char),
+                    None => return format!("${{{name}"),
+                }
+            }
+        }
+        Some('(') => {
+            chars.next();
+            let mut depth = 1;
+            let mut command = String::new();
+            loop {
+                match chars.next() {
+                    Some(')') if depth == 1 => break,
+                    Some(char) => {
+                        match char {
+                            '(' => depth += 1,
+                            ')' => depth -= 1,
+                            _ => {}
+                        }
+                        command.push(char);
+                    }
+                    None => return format!("$({command}"),
+                }
+            }
+            substitute(&command).trim_end_matches('\n').to_owned()
+        }
+        Some(char) if char.is_ascii_alphabetic() || *char == '_' => {
+            let mut name = String::new();
+            while let Some(char) =
+                chars.next_if(|char| char.is_ascii_alphanumeric() || *char == '_')
+            {
+                name.push(char);
+            }
+            std::env::var(name).unwrap_or_default()
+        }
+        _ => "$".to_owned(),
+    }
+}
+
+fn user() -> String {
+    if let Ok(user) = std::env::var("USER") {
+        return user;
+    }
+    let passwd = unsafe { libc::getpwuid(libc::geteuid()) };
+    if passwd.is_null() {
+        return String::new();
+    }
+    unsafe { CStr::from_ptr((*passwd).pw_name) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn hostname() -> String {
+    let mut name = [0u8; 256];
+    if unsafe { libc::gethostname(name.as_mut_ptr().cast(), name.len()) } != 0 {
+        return String::new();
+    }
+    CStr::from_bytes_until_nul(&name)
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// The working directory with `$HOME` shown as `~`, or just its last
+/// component.
+fn working_dir(basename: bool) -> String {
+    let Ok(cwd) = std::env::current_dir() else {
+        return String::new();
+    };
+    let home = std::env::var("HOME").ok().filter(|home| home != "/");
+
+    if let Some(rest) = home.and_then(|home| cwd.strip_prefix(home).ok().map(Path::to_path_buf)) {
+        if rest.as_os_str().is_empty() {
+            return "~".to_owned();
+        }
+        if !basename {
+            return format!("~/{}", rest.display());
+        }
+    }
+    if basename {
+        return cwd.file_name().map_or_else(
+            || "/".to_owned(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+    }
+    cwd.display().to_string()
+}
+
+fn local_time() -> Option<libc::tm> {
+    let now = unsafe { libc::time(std::ptr::null_mut()) };
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&now, &mut tm) }.is_null() {
+        return None;
+    }
+    Some(tm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_with(prompt: &str) -> String {
+        let state = PromptState {
+            history_number: 7,
+            last_status: 2,
+        };
+        expand(prompt, &state, |command| format!("<{command}>\n"))
+    }
+
+    #[test]
+    fn escapes_expand() {
+        assert_eq!(expand_with("\\!:\\?:\\j \\\\ \\n"), "7:2:0 \\ \n");
+        assert_eq!(expand_with("\\e[1m\\101"), "\x1b[1mA");
+        assert_eq!(expand_with("\\q"), "\\q");
+        assert_eq!(
+            expand_with("\\[\\e[32m\\]$ "),
+            format!("{IGNORE_START}\x1b[32m{IGNORE_END}$ ")
+        );
+    }
+
+    #[test]
+    fn time_escapes_have_fixed_widths() {
+        assert_eq!(expand_with("\\t").len(), "00:00:00".len());
+        assert_eq!(expand_with("\\A").len(), "00:00".len());
+        assert_eq!(expand_with("\\d").len(), "Mon Jan 01".len());
+    }
+
+    #[test]
+    fn variables_and_commands_expand() {
+        std::env::set_var("PROMPT_TEST_NAME", "value");
+        assert_eq!(
+            expand_with("$PROMPT_TEST_NAME:${PROMPT_TEST_NAME}x:$?"),
+            "value:valuex:2"
+        );
+        assert_eq!(expand_with("$(echo (a))!"), "<echo (a)>!");
+        assert_eq!(expand_with("$ $1"), "$ $1");
+    }
+
+    #[test]
+    fn unclosed_expansions_are_kept() {
+        assert_eq!(
+            expand_with("${PROMPT_TEST_NAME \\u"),
+            "${PROMPT_TEST_NAME \\u"
+        );
+        assert_eq!(expand_with("$(echo (a) \\!"), "$(echo (a) \\!");
+    }
+
+    #[test]
+    fn expansions_are_not_expanded_again() {
+        // `\$` gives `$` or `#`, which doesn't start a variable
+        assert_eq!(&expand_with("\\$PROMPT_TEST_NAME")[1..], "PROMPT_TEST_NAME");
+        std::env::set_var("PROMPT_TEST_DOLLAR", "$PROMPT_TEST_NAME");
+        assert_eq!(expand_with("$PROMPT_TEST_DOLLAR"), "$PROMPT_TEST_NAME");
+    }
+}
@@ -0,0 +1,75 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+const DEFAULT_COLUMNS: usize = 80;
+const DEFAULT_LINES: usize = 24;
+
+/// Set by SIGWINCH, the size is queried again on the next `size` call.
+static RESIZED: AtomicBool = AtomicBool::new(true);
+static COLUMNS: AtomicUsize = AtomicUsize::new(DEFAULT_COLUMNS);
+static LINES: AtomicUsize = AtomicUsize::new(DEFAULT_LINES);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalSize {
+    pub columns: usize,
+    pub lines: usize,
+}
+
+extern "C" fn on_resize(_signal: libc::c_int) {
+    RESIZED.store(true, Ordering::Relaxed);
+}
+
+/// Installs the SIGWINCH handler. `SA_RESTART` is left out so a pending
+/// read is interrupted and the line can be redrawn at the new width.
+pub fn watch_resize() {
+    unsafe {
+        let mut action: libc::sigaction = std::mem::zeroed();
+        action.sa_sigaction = on_resize as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        libc::sigemptyset(&mut action.sa_mask);
+        libc::sigaction(libc::SIGWINCH, &action, std::ptr::null_mut());
+    }
+}
+
+/// Size of the terminal on stdout. When it is a terminal `COLUMNS` and
+/// `LINES` are exported, otherwise they are read to pick the size.
+pub fn size() -> TerminalSize {
+    if RESIZED.swap(false, Ordering::Relaxed) {
+        let size = match query() {
+            Some(size) => {
+                std::env::set_var("COLUMNS", size.columns.to_string());
+                std::env::set_var("LINES", size.lines.to_string());
+                size
+            }
+            None => TerminalSize {
+                columns: env_size("COLUMNS", DEFAULT_COLUMNS),
+                lines: env_size("LINES", DEFAULT_LINES),
+            },
+        };
+        COLUMNS.store(size.columns, Ordering::Relaxed);
+        LINES.store(size.lines, Ordering::Relaxed);
+    }
+
+    TerminalSize {
+        columns: COLUMNS.load(Ordering::Relaxed),
+        lines: LINES.load(Ordering::Relaxed),
+    }
+}
+
+fn query() -> Option<TerminalSize> {
+    let mut winsize: libc::winsize = unsafe { std::mem::zeroed() };
+    if unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut winsize) } != 0 {
+        return None;
+    }
+
+    (winsize.ws_col > 0).then(|| TerminalSize {
+        columns: usize::from(winsize.ws_col),
+        lines: usize::from(winsize.ws_row),
+    })
+}
+
+fn env_size(name: &str, default: usize) -> usize {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .filter(|&value| value > 0)
+        .unwrap_or(default)
+}
@@ -0,0 +1,61 @@
+mod common;
+use common::run_shell;
+
+#[test]
+fn alias_expands_first_word() {
+    let output = run_shell(&["-c", "alias greet='echo hello'\ngreet world"], "");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(stdout, "hello world\n");
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn alias_lists_definitions() {
+    let output = run_shell(&["-c", "alias b='echo b'; alias a='echo a'; alias"], "");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(stdout, "alias a='echo a'\nalias b='echo b'\n");
+}
+
+#[test]
+fn unalias_removes_definition() {
+    let output = run_shell(&["-c", "alias hi='echo hi'; unalias hi; hi"], "");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(stderr.contains("hi: command not found"));
+    assert_eq!(output.status.code(), Some(127));
+}
+
+#[test]
+fn type_reports_alias() {
+    let output = run_shell(&["-c", "alias ll='ls -la'; type ll"], "");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(stdout, "ll is aliased to `ls -la'\n");
+}
+
+#[test]
+fn self_referencing_alias_does_not_loop() {
+    let output = run_shell(&["-c", "alias echo='echo [x]'; echo done"], "");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(stdout, "[x] done\n");
+}
+
+#[test]
+fn commands_later_in_an_alias_list_are_expanded() {
+    let output = run_shell(
+        &[
+            "-c",
+            "alias greet='echo hi'\nalias x='true; greet && greet'\nalias y='echo y; y'\nx\ny",
+        ],
+        "",
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert_eq!(stdout, "hi\nhi\ny\n");
+    // An alias isn't expanded again in its own text
+    assert!(stderr.contains("y: command not found"), "got: {stderr}");
+}
@@ -15,8 +15,8 @@ fn echo_backslash_escapes_space() {
         .lines()
         .map(|l| {
             let trimmed = l.trim();
-            if trimmed.starts_with("$ ") {
-                trimmed[2..].to_string()
+            if let Some(stripped) = trimmed.strip_prefix("$ ") {
+                stripped.to_string()
             } else if trimmed.starts_with('$') {
                 String::new()
             } else {
@@ -41,8 +41,8 @@ fn echo_multiple_escaped_spaces() {
         .lines()
         .map(|l| {
             let trimmed = l.trim();
-            if trimmed.starts_with("$ ") {
-                trimmed[2..].to_string()
+            if let Some(stripped) = trimmed.strip_prefix("$ ") {
+                stripped.to_string()
             } else if trimmed.starts_with('$') {
                 String::new()
             } else {
@@ -67,8 +67,8 @@ fn echo_backslash_inside_double_quotes_preserved() {
         .lines()
         .map(|l| {
             let trimmed = l.trim();
-            if trimmed.starts_with("$ ") {
-                trimmed[2..].to_string()
+            if let Some(stripped) = trimmed.strip_prefix("$ ") {
+                stripped.to_string()
             } else if trimmed.starts_with('$') {
                 String::new()
             } else {
@@ -93,8 +93,8 @@ fn echo_double_backslash_produces_single() {
         .lines()
         .map(|l| {
             let trimmed = l.trim();
-            if trimmed.starts_with("$ ") {
-                trimmed[2..].to_string()
+            if let Some(stripped) = trimmed.strip_prefix("$ ") {
+                stripped.to_string()
             } else if trimmed.starts_with('$') {
                 String::new()
             } else {
@@ -119,8 +119,8 @@ fn echo_backslash_escapes_special_chars() {
         .lines()
         .map(|l| {
             let trimmed = l.trim();
-            if trimmed.starts_with("$ ") {
-                trimmed[2..].to_string()
+            if let Some(stripped) = trimmed.strip_prefix("$ ") {
+                stripped.to_string()
             } else if trimmed.starts_with('$') {
                 String::new()
             } else {
@@ -194,8 +194,8 @@ fn echo_backslash_not_escape_inside_single_quotes() {
         .lines()
         .map(|l| {
             let trimmed = l.trim();
-            if trimmed.starts_with("$ ") {
-                trimmed[2..].to_string()
+            if let Some(stripped) = trimmed.strip_prefix("$ ") {
+                stripped.to_string()
             } else if trimmed.starts_with('$') {
                 String::new()
             } else {
@@ -221,8 +221,8 @@ fn echo_mixed_quotes_and_backslashes() {
         .lines()
         .map(|l| {
             let trimmed = l.trim();
-            if trimmed.starts_with("$ ") {
-                trimmed[2..].to_string()
+            if let Some(stripped) = trimmed.strip_prefix("$ ") {
+                stripped.to_string()
             } else if trimmed.starts_with('$') {
                 String::new()
             } else {
@@ -247,8 +247,8 @@ fn echo_escaped_quotes() {
         .lines()
         .map(|l| {
             let trimmed = l.trim();
-            if trimmed.starts_with("$ ") {
-                trimmed[2..].to_string()
+            if let Some(stripped) = trimmed.strip_prefix("$ ") {
+                stripped.to_string()
             } else if trimmed.starts_with('$') {
                 String::new()
             } else {
@@ -273,8 +273,8 @@ fn echo_backslash_with_regular_chars() {
         .lines()
         .map(|l| {
             let trimmed = l.trim();
-            if trimmed.starts_with("$ ") {
-                trimmed[2..].to_string()
+            if let Some(stripped) = trimmed.strip_prefix("$ ") {
+                stripped.to_string()
             } else if trimmed.starts_with('$') {
                 String::new()
             } else {
@@ -299,8 +299,8 @@ fn echo_mixed_escaped_and_unescaped_spaces() {
         .lines()
         .map(|l| {
             let trimmed = l.trim();
-            if trimmed.starts_with("$ ") {
-                trimmed[2..].to_string()
+            if let Some(stripped) = trimmed.strip_prefix("$ ") {
+                stripped.to_string()
             } else if trimmed.starts_with('$') {
                 String::new()
             } else {
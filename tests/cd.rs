@@ -140,7 +140,6 @@ fn cd_then_external_command() {
 #[test]
 fn cd_affects_relative_file_operations() {
     let output = test_case("cd /\nls tmp", true);
-    let stdout = String::from_utf8_lossy(&output.stdout);
 
     // After cd /, ls tmp should list /tmp contents
     // (This tests that cd actually changes the working directory for child processes)
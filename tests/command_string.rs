@@ -0,0 +1,144 @@
+mod common;
+use common::run_shell;
+
+#[test]
+fn command_string_runs_sequence() {
+    let output = run_shell(&["-c", "echo first; echo second"], "");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(stdout, "first\nsecond\n");
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn command_string_and_or_lists() {
+    let output = run_shell(&["-c", "nosuchcmd && echo skipped || echo fallback"], "");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(stdout, "fallback\n");
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn command_string_exit_status_is_last_command() {
+    let output = run_shell(&["-c", "echo ok; nosuchcmd"], "");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(stderr.contains("nosuchcmd: command not found"));
+    assert_eq!(output.status.code(), Some(127));
+}
+
+#[test]
+fn command_string_exit_builtin() {
+    let output = run_shell(&["-c", "exit 7; echo unreachable"], "");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.is_empty());
+    assert_eq!(output.status.code(), Some(7));
+}
+
+#[test]
+fn errexit_stops_on_failure() {
+    let output = run_shell(
+        &[
+            "-e",
+            "-c",
+            "echo before; ls /this/does/not/exist; echo after",
+        ],
+        "",
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(stdout, "before\n");
+    assert_ne!(output.status.code(), Some(0));
+}
+
+#[test]
+fn errexit_ignores_failures_in_and_or_lists() {
+    let output = run_shell(&["-ec", "nosuchcmd || echo recovered; echo after"], "");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(stdout, "recovered\nafter\n");
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn xtrace_prints_commands_to_stderr() {
+    let output = run_shell(&["-x", "-c", "echo traced"], "");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert_eq!(stdout, "traced\n");
+    assert!(stderr.contains("+ echo traced"));
+}
+
+#[test]
+fn script_file_operand() {
+    let script = "/tmp/test_command_string_script.sh";
+    std::fs::write(script, "# comment\necho from script\n").unwrap();
+
+    let output = run_shell(&[script], "");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(stdout, "from script\n");
+    assert_eq!(output.status.code(), Some(0));
+
+    std::fs::remove_file(script).ok();
+}
+
+#[test]
+fn unreadable_script_file_is_reported() {
+    let output = run_shell(&["/nonexistent/script.sh"], "");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(
+        stderr.contains("/nonexistent/script.sh: No such file or directory"),
+        "got: {stderr}"
+    );
+    assert_eq!(output.status.code(), Some(127));
+
+    let output = run_shell(&["/tmp"], "");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(stderr.contains("/tmp: Is a directory"), "got: {stderr}");
+    assert_eq!(output.status.code(), Some(126));
+}
+
+#[test]
+fn missing_command_string_is_an_error() {
+    let output = run_shell(&["-c"], "");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(stderr.contains("-c: option requires an argument"));
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn invalid_option_prints_usage() {
+    let output = run_shell(&["-z"], "");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(stderr.contains("-z: invalid option"));
+    assert!(stderr.contains("Usage:"));
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn help_and_version() {
+    let output = run_shell(&["--help"], "");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Usage:"));
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = run_shell(&["--version"], "");
+    assert!(String::from_utf8_lossy(&output.stdout).contains(env!("CARGO_PKG_VERSION")));
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn stdin_mode_reads_commands() {
+    let output = run_shell(&["-s"], "echo from stdin\nexit 3\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("from stdin"));
+    assert_eq!(output.status.code(), Some(3));
+}
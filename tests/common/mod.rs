@@ -1,11 +1,31 @@
+#![allow(dead_code)]
+
 use std::{
     io::Write,
     process::{Command, Stdio},
 };
 
 pub fn test_case(command: &str, should_exit: bool) -> std::process::Output {
-    let mut child_proc = Command::new("cargo")
-        .args(["run", "--quiet"])
+    let command = if should_exit {
+        command.to_string() + "\nexit\n"
+    } else {
+        command.to_owned()
+    };
+    run_shell(&[], &command)
+}
+
+pub fn run_shell(args: &[&str], input: &str) -> std::process::Output {
+    run_shell_with_env(args, &[], input)
+}
+
+pub fn run_shell_with_env(
+    args: &[&str],
+    envs: &[(&str, &str)],
+    input: &str,
+) -> std::process::Output {
+    let mut child_proc = Command::new(env!("CARGO_BIN_EXE_codecrafters-shell"))
+        .args(args)
+        .envs(envs.iter().copied())
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
@@ -14,13 +34,8 @@ pub fn test_case(command: &str, should_exit: bool) -> std::process::Output {
 
     {
         let stdin = child_proc.stdin.as_mut().expect("Failed to open stdin");
-        let command = if should_exit {
-            command.to_string() + "\nexit\n"
-        } else {
-            command.to_owned()
-        };
         stdin
-            .write_all(command.as_bytes())
+            .write_all(input.as_bytes())
             .expect("failed to write to stdin");
     }
 
@@ -0,0 +1,217 @@
+mod common;
+use common::run_shell_with_env;
+
+fn run_in_tempdir(keys: &str) -> String {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("src")).unwrap();
+    std::fs::write(dir.path().join("src/main.rs"), "").unwrap();
+    std::fs::write(dir.path().join("src/mod.rs"), "").unwrap();
+    std::fs::write(dir.path().join("script"), "").unwrap();
+    std::fs::create_dir(dir.path().join("My Documents")).unwrap();
+    std::fs::write(dir.path().join("My Documents/notes.txt"), "").unwrap();
+    std::fs::write(dir.path().join("a'b"), "").unwrap();
+
+    let input = format!("cd {}\n{keys}\nexit\n", dir.path().display());
+    let output = run_shell_with_env(&[], &[("PATH", "/nonexistent")], &input);
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn tab_completes_file_argument() {
+    let stdout = run_in_tempdir("echo src/ma\t");
+    assert!(stdout.contains("\nsrc/main.rs\n"), "got: {stdout}");
+}
+
+#[test]
+fn directory_completes_with_slash() {
+    let stdout = run_in_tempdir("echo sr\tmod.rs");
+    assert!(stdout.contains("\nsrc/mod.rs\n"), "got: {stdout}");
+}
+
+#[test]
+fn double_tab_lists_directory_entries() {
+    let stdout = run_in_tempdir("echo src/m\t\t\x03");
+    assert!(
+        stdout.contains("\r\nmain.rs  mod.rs\x1b[1A"),
+        "got: {stdout}"
+    );
+}
+
+#[test]
+fn special_characters_are_escaped() {
+    let stdout = run_in_tempdir("cd My\t\npwd");
+    assert!(stdout.contains("$ cd My\\ Documents/"), "got: {stdout}");
+    assert!(stdout.contains("/My Documents\n"), "got: {stdout}");
+}
+
+#[test]
+fn completion_stays_inside_open_quotes() {
+    let stdout = run_in_tempdir("echo 'a\t");
+    assert!(stdout.contains("$ echo 'a'\\''b' "), "got: {stdout}");
+    assert!(stdout.contains("\na'b\n"), "got: {stdout}");
+
+    let stdout = run_in_tempdir("echo \"My\tno\t");
+    assert!(
+        stdout.contains("$ echo \"My Documents/notes.txt\" "),
+        "got: {stdout}"
+    );
+    assert!(
+        stdout.contains("\nMy Documents/notes.txt\n"),
+        "got: {stdout}"
+    );
+}
+
+#[test]
+fn compignorecase_matches_in_any_case() {
+    let stdout = run_in_tempdir("echo my\t");
+    assert!(
+        !stdout.contains(
+            "
+My Documents/
+"
+        ),
+        "got: {stdout}"
+    );
+
+    let stdout = run_in_tempdir("set -o compignorecase\necho my\tNO\t");
+    assert!(
+        stdout.contains("\nMy Documents/notes.txt\n"),
+        "got: {stdout}"
+    );
+}
+
+#[test]
+fn compfuzzy_matches_subsequences() {
+    let stdout = run_in_tempdir("set -o compfuzzy\necho scp\t");
+    assert!(stdout.contains("\nscript\n"), "got: {stdout}");
+}
+
+#[test]
+fn tab_cycles_through_the_menu() {
+    let stdout = run_in_tempdir("echo src/m\t\t\t\t");
+    assert!(
+        stdout.contains("main.rs  \x1b[7mmod.rs\x1b[27m"),
+        "got: {stdout}"
+    );
+    assert!(stdout.contains("\nsrc/mod.rs\n"), "got: {stdout}");
+
+    // Shift-Tab starts from the last match
+    let stdout = run_in_tempdir("echo src/m\x1b[Z");
+    assert!(stdout.contains("\nsrc/mod.rs\n"), "got: {stdout}");
+}
+
+fn run_with_many_files(keys: &str) -> String {
+    let dir = tempfile::tempdir().unwrap();
+    for idx in 0..120 {
+        std::fs::write(dir.path().join(format!("f{idx:03}")), "").unwrap();
+    }
+
+    let input = format!("cd {}\n{keys}\x03exit\n", dir.path().display());
+    let output = run_shell_with_env(&[], &[("LINES", "5")], &input);
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn many_matches_ask_before_listing() {
+    let stdout = run_with_many_files("echo f\t\tn");
+    assert!(
+        stdout.contains("Display all 120 possibilities? (y or n)"),
+        "got: {stdout}"
+    );
+    assert!(!stdout.contains("f000"), "got: {stdout}");
+}
+
+#[test]
+fn long_listings_are_paged() {
+    let stdout = run_with_many_files("echo f\t\ty q");
+    assert_eq!(stdout.matches("--More--").count(), 2, "got: {stdout}");
+    assert!(stdout.contains("f007"), "got: {stdout}");
+    assert!(!stdout.contains("f008"), "got: {stdout}");
+}
+
+#[test]
+fn cd_completes_directories_only() {
+    let stdout = run_in_tempdir("cd s\t\npwd");
+    assert!(stdout.contains("/src\n"), "got: {stdout}");
+}
+
+#[test]
+fn type_completes_command_names() {
+    let stdout = run_in_tempdir("type ech\t");
+    assert!(
+        stdout.contains("\necho is a shell builtin\n"),
+        "got: {stdout}"
+    );
+}
+
+#[test]
+fn dollar_completes_variable_names() {
+    let output = run_shell_with_env(
+        &[],
+        &[("COMPLETION_VARIABLE", "1")],
+        "echo $COMPLETION_VAR\t\x03exit\n",
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("$ echo $COMPLETION_VARIABLE "),
+        "got: {stdout}"
+    );
+}
+
+#[test]
+fn complete_wordlist_completes_arguments() {
+    let stdout = run_in_tempdir("complete -W 'alpha beta' echo\necho al\t");
+    assert!(stdout.contains("\nalpha\n"), "got: {stdout}");
+}
+
+#[test]
+fn complete_command_generates_candidates() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let generator = dir.path().join("gen");
+    // Arguments are the command name, the word and the previous word
+    std::fs::write(&generator, "#!/bin/sh\necho \"$2\"one\necho \"$3\"\n").unwrap();
+    std::fs::set_permissions(&generator, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let path = format!("{}:/usr/bin:/bin", dir.path().display());
+    let output = run_shell_with_env(
+        &[],
+        &[("PATH", &path)],
+        "complete -C gen echo\necho x\t\nexit\n",
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("\nxone\n"), "got: {stdout}");
+}
+
+#[test]
+fn complete_command_gets_comp_line_for_itself_only() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let generator = dir.path().join("gen");
+    std::fs::write(&generator, "#!/bin/sh\necho \"$2$COMP_POINT\"\n").unwrap();
+    std::fs::set_permissions(&generator, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let path = format!("{}:/usr/bin:/bin", dir.path().display());
+    let output = run_shell_with_env(
+        &[],
+        &[("PATH", &path)],
+        "complete -C gen echo\necho x\t\nprintenv COMP_LINE || echo unset\nexit\n",
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("\nx6\n"), "got: {stdout}");
+    assert!(stdout.contains("\nunset\n"), "got: {stdout}");
+}
+
+#[test]
+fn compgen_prints_candidates() {
+    let output = run_shell_with_env(
+        &["-c", "compgen -W 'start stop status' sta; compgen -d nope"],
+        &[],
+        "",
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "start\nstatus\n");
+    assert_eq!(output.status.code(), Some(1));
+}
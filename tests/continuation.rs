@@ -0,0 +1,41 @@
+mod common;
+use common::{run_shell, run_shell_with_env};
+
+fn run_lines(input: &str) -> String {
+    let output = run_shell(&[], &format!("{input}\nexit\n"));
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn unclosed_quote_continues_on_next_line() {
+    let stdout = run_lines("echo 'hello\nworld'");
+    assert!(stdout.contains("\r\n> world'"), "got: {stdout}");
+    assert!(stdout.contains("\nhello\nworld\n"), "got: {stdout}");
+}
+
+#[test]
+fn trailing_backslash_joins_lines() {
+    let stdout = run_lines("echo one \\\ntwo");
+    assert!(stdout.contains("\none two\n"), "got: {stdout}");
+}
+
+#[test]
+fn trailing_operator_continues_list() {
+    let stdout = run_lines("true &&\necho done");
+    assert!(stdout.contains("\ndone\n"), "got: {stdout}");
+}
+
+#[test]
+fn ps2_sets_continuation_prompt() {
+    let output = run_shell_with_env(&[], &[("PS2", "... ")], "echo \"a\nb\"\nexit\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("\r\n... b\""), "got: {stdout}");
+    assert!(stdout.contains("\na\nb\n"), "got: {stdout}");
+}
+
+#[test]
+fn ctrl_c_aborts_multi_line_entry() {
+    let stdout = run_lines("echo 'never\nstill\x03\necho after");
+    assert!(!stdout.contains("never\nstill"), "got: {stdout}");
+    assert!(stdout.contains("\nafter\n"), "got: {stdout}");
+}
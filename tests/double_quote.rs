@@ -19,14 +19,7 @@ fn echo_double_quotes_vs_no_quotes_spaces() {
     let stdout = String::from_utf8_lossy(&output.stdout);
     
     let outputs: Vec<String> = stdout.lines()
-        .filter_map(|l| {
-            let trimmed = l.trim();
-            if trimmed.starts_with("$ ") {
-                Some(trimmed[2..].to_string())
-            } else {
-                None
-            }
-        })
+        .filter_map(|l| l.trim().strip_prefix("$ ").map(str::to_string))
         .filter(|s| !s.is_empty() && s != "exit")
         .collect();
     
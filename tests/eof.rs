@@ -0,0 +1,78 @@
+mod common;
+use common::{run_shell, run_shell_with_env};
+
+#[test]
+fn end_of_input_exits_with_last_status() {
+    let output = run_shell(&[], "echo hi\nfalse\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stderr.is_empty(), "got: {output:?}");
+}
+
+#[test]
+fn ctrl_d_on_empty_line_exits() {
+    let output = run_shell(&[], "\x04echo never\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(!stdout.contains("\nnever\n"), "got: {stdout}");
+}
+
+#[test]
+fn ctrl_d_deletes_when_line_is_not_empty() {
+    let output = run_shell(&[], "echo hi!\x02\x04\nexit\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("\nhi\n"), "got: {stdout}");
+}
+
+#[test]
+fn unterminated_last_line_runs() {
+    let output = run_shell(&[], "echo tail");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("\ntail\n"), "got: {stdout}");
+}
+
+#[test]
+fn ignoreeof_requires_exit() {
+    let output = run_shell(&[], "set -o ignoreeof\n\x04echo still\n\x04exit 3\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Use \"exit\" to leave the shell.\n"),
+        "got: {stdout}"
+    );
+    assert!(stdout.contains("\nstill\n"), "got: {stdout}");
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn ignoreeof_count_is_configurable() {
+    let output = run_shell_with_env(
+        &[],
+        &[("IGNOREEOF", "1")],
+        "set -o ignoreeof\n\x04\x04echo never\n",
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.matches("Use \"exit\"").count(), 1, "got: {stdout}");
+    assert!(!stdout.contains("\nnever\n"), "got: {stdout}");
+}
+
+#[test]
+fn unfinished_entry_at_end_of_input_is_an_error() {
+    let output = run_shell(&[], "echo 'open\n");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("syntax error: unexpected end of file"),
+        "got: {stderr}"
+    );
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn history_is_saved_on_end_of_input() {
+    let dir = tempfile::tempdir().unwrap();
+    let histfile = dir.path().join("history");
+    run_shell_with_env(
+        &["-i", "--norc"],
+        &[("HISTFILE", histfile.to_str().unwrap())],
+        "echo saved\n",
+    );
+    assert_eq!(std::fs::read_to_string(&histfile).unwrap(), "echo saved\n");
+}
@@ -142,3 +142,23 @@ fn external_command_false_fails() {
     // Shell itself should exit with 0 (from our exit command)
     assert_eq!(output.status.code(), Some(0));
 }
+
+#[test]
+fn empty_path_entry_is_the_current_directory() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let script = dir.path().join("greet");
+    std::fs::write(&script, "#!/bin/sh\necho hello from greet\n").unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let input = format!("cd {}\ngreet\ntype greet\nexit\n", dir.path().display());
+    let output = common::run_shell_with_env(&[], &[("PATH", ":/usr/bin:/bin")], &input);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("hello from greet\n"), "got: {stdout}");
+    assert!(
+        stdout.contains("greet is hashed (./greet)\n"),
+        "got: {stdout}"
+    );
+}
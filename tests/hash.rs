@@ -0,0 +1,31 @@
+mod common;
+use common::test_case;
+
+#[test]
+fn commands_run_are_hashed() {
+    let output = test_case("hash\ntrue\ntrue\nhash\ntype true", true);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("hash: hash table empty\n"), "got: {stdout}");
+    assert!(stdout.contains("hits\tcommand\n   2\t/"), "got: {stdout}");
+    assert!(stdout.contains("true is hashed (/"), "got: {stdout}");
+}
+
+#[test]
+fn hash_p_sets_the_path_run() {
+    let output = test_case("hash -p /bin/echo greet\ngreet hello\nhash -t greet", true);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("hello\n"), "got: {stdout}");
+    assert!(stdout.contains("/bin/echo\n"), "got: {stdout}");
+}
+
+#[test]
+fn hash_r_forgets_commands() {
+    let output = test_case("true\nhash -r\nhash\nhash -t true", true);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(stdout.contains("hash: hash table empty\n"), "got: {stdout}");
+    assert!(stderr.contains("hash: true: not found"), "got: {stderr}");
+}
@@ -0,0 +1,127 @@
+mod common;
+use common::{run_shell, run_shell_with_env};
+
+fn run_interactive(histfile: &std::path::Path, envs: &[(&str, &str)], input: &str) -> String {
+    let mut envs = envs.to_vec();
+    envs.push(("HISTFILE", histfile.to_str().unwrap()));
+    let output = run_shell_with_env(&["-i", "--norc"], &envs, input);
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn history_lists_entered_lines() {
+    let dir = tempfile::tempdir().unwrap();
+    let histfile = dir.path().join("history");
+
+    let stdout = run_interactive(&histfile, &[], "echo one\nhistory\nexit\n");
+
+    assert!(stdout.contains("    1  echo one\n"), "got: {stdout}");
+    assert!(stdout.contains("    2  history\n"), "got: {stdout}");
+}
+
+#[test]
+fn history_persists_across_sessions() {
+    let dir = tempfile::tempdir().unwrap();
+    let histfile = dir.path().join("history");
+
+    run_interactive(&histfile, &[], "echo first\nexit\n");
+    let stdout = run_interactive(&histfile, &[], "history\nexit\n");
+
+    assert!(stdout.contains("    1  echo first\n"), "got: {stdout}");
+    assert_eq!(
+        std::fs::read_to_string(&histfile).unwrap(),
+        "echo first\nexit\nhistory\nexit\n"
+    );
+}
+
+#[test]
+fn histfilesize_limits_saved_lines() {
+    let dir = tempfile::tempdir().unwrap();
+    let histfile = dir.path().join("history");
+
+    run_interactive(
+        &histfile,
+        &[("HISTFILESIZE", "2")],
+        "echo a\necho b\nexit\n",
+    );
+
+    assert_eq!(
+        std::fs::read_to_string(&histfile).unwrap(),
+        "echo b\nexit\n"
+    );
+}
+
+#[test]
+fn histcontrol_ignores_dups_and_space() {
+    let dir = tempfile::tempdir().unwrap();
+    let histfile = dir.path().join("history");
+
+    let stdout = run_interactive(
+        &histfile,
+        &[("HISTCONTROL", "ignoredups:ignorespace")],
+        "pwd\npwd\n echo secret\nhistory\nexit\n",
+    );
+
+    assert!(
+        stdout.contains("    1  pwd\n    2  history\n"),
+        "got: {stdout}"
+    );
+}
+
+#[test]
+fn history_clear_and_write() {
+    let dir = tempfile::tempdir().unwrap();
+    let histfile = dir.path().join("history");
+
+    run_interactive(&histfile, &[], "echo a\nhistory -c\nhistory -w\nexit\n");
+
+    assert_eq!(
+        std::fs::read_to_string(&histfile).unwrap(),
+        "history -w\nexit\n"
+    );
+}
+
+#[test]
+fn up_arrow_recalls_previous_lines() {
+    let output = run_shell(&[], "echo first\necho second\n\x1b[A\x1b[A\nexit\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(stdout.matches("\nfirst\n").count(), 2, "got: {stdout}");
+}
+
+#[test]
+fn down_arrow_restores_draft() {
+    let output = run_shell(&[], "echo stored\nec\x1b[A\x1b[Bho draft\nexit\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("\ndraft\n"), "got: {stdout}");
+}
+
+#[test]
+fn page_up_searches_by_prefix() {
+    let output = run_shell(&[], "echo alpha\npwd\necho\x1b[5~\nexit\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(stdout.matches("\nalpha\n").count(), 2, "got: {stdout}");
+}
+
+#[test]
+fn ctrl_r_runs_matching_entry() {
+    let output = run_shell(&[], "echo alpha\necho beta\n\x12alp\nexit\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("(reverse-i-search)'alp': echo alpha"),
+        "got: {stdout}"
+    );
+    assert_eq!(stdout.matches("\nalpha\n").count(), 2, "got: {stdout}");
+}
+
+#[test]
+fn ctrl_g_cancels_search() {
+    let output = run_shell(&[], "echo one\necho kept\x12one\x07\nexit\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("\nkept\n"), "got: {stdout}");
+    assert_eq!(stdout.matches("\none\n").count(), 1, "got: {stdout}");
+}
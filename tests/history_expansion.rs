@@ -0,0 +1,54 @@
+mod common;
+use common::{run_shell, test_case};
+
+#[test]
+fn bang_bang_repeats_and_echoes_last_command() {
+    let output = test_case("echo hello\necho !!", true);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("\necho echo hello\n"), "got: {stdout}");
+    assert!(stdout.contains("\necho hello\n"), "got: {stdout}");
+}
+
+#[test]
+fn bang_dollar_uses_last_argument() {
+    let output = test_case("echo one two\necho !$", true);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.ends_with("echo two\ntwo\n$ exit\r\n"),
+        "got: {stdout}"
+    );
+}
+
+#[test]
+fn quick_substitution_reruns_previous_line() {
+    let output = test_case("echo foo\n^foo^bar", true);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("\necho bar\nbar\n"), "got: {stdout}");
+}
+
+#[test]
+fn single_quotes_disable_expansion() {
+    let output = test_case("echo one\necho '!!'", true);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("\n!!\n"), "got: {stdout}");
+}
+
+#[test]
+fn missing_event_is_reported() {
+    let output = test_case("!nosuchprefix", true);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(stderr.contains("!nosuchprefix: event not found"));
+}
+
+#[test]
+fn set_plus_h_disables_expansion() {
+    let output = run_shell(&[], "set +H\necho !!\nexit\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("\n!!\n"), "got: {stdout}");
+}
@@ -0,0 +1,89 @@
+mod common;
+use common::run_shell;
+
+fn run_line(keys: &str) -> String {
+    let output = run_shell(&[], &format!("{keys}\nexit\n"));
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn insert_in_the_middle_of_the_line() {
+    let stdout = run_line("eco\x1b[Dh\x1b[F hi");
+    assert!(stdout.contains("\nhi\n"), "got: {stdout}");
+}
+
+#[test]
+fn ctrl_a_moves_to_start_of_line() {
+    let stdout = run_line("cho hi\x01e");
+    assert!(stdout.contains("\nhi\n"), "got: {stdout}");
+}
+
+#[test]
+fn ctrl_d_and_delete_remove_under_cursor() {
+    let stdout = run_line("eecho  hi\x01\x04\x06\x06\x06\x06\x1b[3~");
+    assert!(stdout.contains("\nhi\n"), "got: {stdout}");
+}
+
+#[test]
+fn alt_b_moves_back_a_word() {
+    let stdout = run_line("echo world\x1bbhello ");
+    assert!(stdout.contains("\nhello world\n"), "got: {stdout}");
+}
+
+#[test]
+fn tab_completes_word_under_cursor() {
+    let stdout = run_line("ech done\x01\x06\x06\x06\t");
+    assert!(stdout.contains("\ndone\n"), "got: {stdout}");
+}
+
+#[test]
+fn consecutive_kills_are_yanked_together() {
+    let stdout = run_line("echo hello world\x17\x17\x19");
+    assert!(stdout.contains("\nhello world\n"), "got: {stdout}");
+}
+
+#[test]
+fn ctrl_k_kills_to_end_of_line() {
+    let stdout = run_line("echo kept gone\x1bb\x0b");
+    assert!(stdout.contains("\nkept\n"), "got: {stdout}");
+}
+
+#[test]
+fn alt_y_rotates_to_older_kill() {
+    let stdout = run_line("echo first\x15echo second\x15\x19\x1by");
+    assert!(stdout.contains("\nfirst\n"), "got: {stdout}");
+}
+
+#[test]
+fn multibyte_input_is_kept() {
+    let stdout = run_line("echo 日本 é 🦀");
+    assert!(stdout.contains("\n日本 é 🦀\n"), "got: {stdout}");
+}
+
+#[test]
+fn backspace_removes_whole_grapheme_cluster() {
+    let stdout = run_line("echo ae\u{301}\x7fb");
+    assert!(stdout.contains("\nab\n"), "got: {stdout}");
+}
+
+#[test]
+fn wide_characters_move_cursor_by_display_width() {
+    let stdout = run_line("echo 日本x\x1b[D\x1b[D");
+    assert!(stdout.contains("\x1b[3D"), "got: {stdout:?}");
+}
+
+#[test]
+fn ctrl_l_clears_screen_and_redraws_line() {
+    let stdout = run_line("echo hi\x02\x0c");
+    assert!(
+        stdout.contains("\x1b[H\x1b[2J$ echo hi\x1b[1D"),
+        "got: {stdout:?}"
+    );
+    assert!(stdout.contains("\nhi\n"), "got: {stdout}");
+}
+
+#[test]
+fn clear_builtin_clears_screen() {
+    let stdout = run_line("clear");
+    assert!(stdout.contains("\n\x1b[H\x1b[2J$ "), "got: {stdout:?}");
+}
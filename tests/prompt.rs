@@ -0,0 +1,55 @@
+mod common;
+use common::run_shell_with_env;
+
+#[test]
+fn ps1_escapes_are_expanded() {
+    let dir = tempfile::tempdir().unwrap();
+    let name = dir.path().file_name().unwrap().to_string_lossy();
+    let input = format!("cd {}\nexit\n", dir.path().display());
+    let output = run_shell_with_env(&[], &[("PS1", "[\\W]\\$ ")], &input);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains(&format!("[{name}]$ exit")) || stdout.contains(&format!("[{name}]# exit")),
+        "got: {stdout}"
+    );
+}
+
+#[test]
+fn ps1_expands_status_history_and_commands() {
+    let output = run_shell_with_env(&[], &[("PS1", "\\! $? $(echo hi)> ")], "false\nexit\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("1 0 hi> false"), "got: {stdout}");
+    assert!(stdout.contains("2 1 hi> exit"), "got: {stdout}");
+}
+
+#[test]
+fn ps1_command_substitution_runs_lists_and_aliases() {
+    let output = run_shell_with_env(
+        &[],
+        &[("PS1", "$(echo a; echo b && greet)> ")],
+        "alias greet='echo c'\nexit\n",
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("a\r\nb\r\nc> exit"), "got: {stdout}");
+}
+
+#[test]
+fn ps1_command_substitution_leaves_the_shell_alone() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run_shell_with_env(
+        &[],
+        &[("PS1", "$(cd /; alias gone='echo leaked'; exit 4)> ")],
+        &format!("cd {}\npwd\ngone\necho alive\n", dir.path().display()),
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains(&format!("> pwd\r\n{}\n", dir.path().display())),
+        "got: {stdout}"
+    );
+    assert!(!stdout.contains("leaked"), "got: {stdout}");
+    assert!(stdout.contains("alive"), "got: {stdout}");
+}
@@ -14,7 +14,7 @@ fn echo_redirect_to_file() {
     // Clean up any existing file
     fs::remove_file(output_file).ok();
     
-    let output = test_case("echo hello world > /tmp/test_echo_redirect.txt", true);
+    let _output = test_case("echo hello world > /tmp/test_echo_redirect.txt", true);
     
     // Check that file was created
     assert!(
@@ -41,7 +41,7 @@ fn echo_redirect_with_quoted_filename() {
     // Clean up any existing file
     fs::remove_file(output_file).ok();
     
-    let output = test_case("echo test > \"/tmp/test file with spaces.txt\"", true);
+    let _output = test_case("echo test > \"/tmp/test file with spaces.txt\"", true);
     
     // Check that file was created
     assert!(
@@ -64,7 +64,7 @@ fn redirect_overwrites_existing_file() {
     // Create file with initial content
     fs::write(output_file, "old content").unwrap();
     
-    let output = test_case("echo new content > /tmp/test_overwrite.txt", true);
+    let _output = test_case("echo new content > /tmp/test_overwrite.txt", true);
     
     // Check that file was overwritten
     let contents = fs::read_to_string(output_file).unwrap();
@@ -85,7 +85,7 @@ fn external_command_redirect() {
     // Clean up any existing file
     fs::remove_file(output_file).ok();
     
-    let output = test_case("ls /tmp > /tmp/test_ls_redirect.txt", true);
+    let _output = test_case("ls /tmp > /tmp/test_ls_redirect.txt", true);
     
     // Check that file was created
     assert!(
@@ -111,7 +111,7 @@ fn redirect_with_special_characters_in_output() {
     // Clean up any existing file
     fs::remove_file(output_file).ok();
     
-    let output = test_case("echo 'hello world' > /tmp/test_special_chars.txt", true);
+    let _output = test_case("echo 'hello world' > /tmp/test_special_chars.txt", true);
     
     // Check that file was created
     assert!(
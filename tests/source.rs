@@ -0,0 +1,87 @@
+mod common;
+use common::run_shell;
+
+fn write_script(path: &str, content: &str) {
+    std::fs::write(path, content).unwrap();
+}
+
+#[test]
+fn source_runs_in_current_shell() {
+    let script = "/tmp/test_source_cd.sh";
+    write_script(script, "cd /tmp\n");
+
+    let output = run_shell(&["-c", &format!("source {script}; pwd")], "");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(stdout, "/tmp\n");
+    assert_eq!(output.status.code(), Some(0));
+
+    std::fs::remove_file(script).ok();
+}
+
+#[test]
+fn dot_is_an_alias_for_source() {
+    let script = "/tmp/test_source_dot.sh";
+    write_script(script, "echo dotted\n");
+
+    let output = run_shell(&["-c", &format!(". {script}")], "");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(stdout, "dotted\n");
+
+    std::fs::remove_file(script).ok();
+}
+
+#[test]
+fn source_return_stops_sourced_file_only() {
+    let script = "/tmp/test_source_return.sh";
+    write_script(script, "echo before\nreturn 3\necho after\n");
+
+    let output = run_shell(&["-c", &format!("source {script} || echo status")], "");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(stdout, "before\nstatus\n");
+    assert_eq!(output.status.code(), Some(0));
+
+    std::fs::remove_file(script).ok();
+}
+
+#[test]
+fn source_missing_file() {
+    let output = run_shell(&["-c", "source /tmp/does/not/exist.sh"], "");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(
+        stderr.contains("/tmp/does/not/exist.sh: No such file or directory"),
+        "got: {stderr}"
+    );
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn type_reports_source_as_builtin() {
+    let output = run_shell(&["-c", "type source; type ."], "");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("source is a shell builtin"));
+    assert!(stdout.contains(". is a shell builtin"));
+}
+
+#[test]
+fn source_nesting_is_limited() {
+    let script = "/tmp/test_source_self.sh";
+    write_script(script, &format!("source {script}\n"));
+
+    let output = run_shell(&["-c", &format!("source {script}; echo after")], "");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(
+        stderr.contains("maximum source nesting exceeded"),
+        "got: {stderr}"
+    );
+    assert_eq!(stdout, "after\n");
+    assert_eq!(output.status.code(), Some(0));
+
+    std::fs::remove_file(script).ok();
+}
@@ -0,0 +1,87 @@
+mod common;
+use common::run_shell_with_env;
+
+fn home_with(files: &[(&str, &str)]) -> tempfile::TempDir {
+    let home = tempfile::tempdir().unwrap();
+    for (name, content) in files {
+        std::fs::write(home.path().join(name), content).unwrap();
+    }
+    home
+}
+
+#[test]
+fn interactive_shell_sources_shellrc() {
+    let home = home_with(&[(".shellrc", "cd /tmp\n")]);
+    let home_path = home.path().to_str().unwrap();
+
+    let output = run_shell_with_env(&["-i"], &[("HOME", home_path)], "pwd\nexit\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("/tmp"), "rc file should run, got: {stdout}");
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn shellrc_path_can_be_overridden() {
+    let home = home_with(&[("custom_rc", "echo custom rc\n")]);
+    let rc_path = home.path().join("custom_rc");
+
+    let output = run_shell_with_env(
+        &["-i"],
+        &[
+            ("HOME", home.path().to_str().unwrap()),
+            ("SHELLRC", rc_path.to_str().unwrap()),
+        ],
+        "exit\n",
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("custom rc"));
+}
+
+#[test]
+fn norc_skips_shellrc() {
+    let home = home_with(&[(".shellrc", "echo should not run\n")]);
+    let home_path = home.path().to_str().unwrap();
+
+    let output = run_shell_with_env(&["-i", "--norc"], &[("HOME", home_path)], "exit\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(!stdout.contains("should not run"));
+}
+
+#[test]
+fn non_interactive_shell_skips_shellrc() {
+    let home = home_with(&[(".shellrc", "echo should not run\n")]);
+    let home_path = home.path().to_str().unwrap();
+
+    let output = run_shell_with_env(&[], &[("HOME", home_path)], "exit\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(!stdout.contains("should not run"));
+}
+
+#[test]
+fn shellrc_errors_report_line_and_continue() {
+    let home = home_with(&[(".shellrc", "echo one\n\nnosuchcmd\necho two\n")]);
+    let home_path = home.path().to_str().unwrap();
+
+    let output = run_shell_with_env(&["-i"], &[("HOME", home_path)], "exit\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(stderr.contains(".shellrc: line 3: nosuchcmd: command not found"));
+    assert!(stdout.contains("one") && stdout.contains("two"));
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn login_shell_sources_profile() {
+    let home = home_with(&[(".profile", "echo profile loaded\n")]);
+    let home_path = home.path().to_str().unwrap();
+
+    let output = run_shell_with_env(&["-l"], &[("HOME", home_path)], "exit\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("profile loaded"));
+}
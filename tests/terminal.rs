@@ -0,0 +1,48 @@
+mod common;
+use std::os::unix::fs::PermissionsExt;
+
+use common::run_shell_with_env;
+
+#[test]
+fn editing_a_wrapped_line_repaints_from_first_row() {
+    let output = run_shell_with_env(
+        &[],
+        &[("COLUMNS", "20")],
+        "echo aaaaaaaaaaaaaaaaaaaaaaaa\x1bbb \nexit\n",
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // The line spans two rows, the repaint moves up before redrawing
+    assert!(stdout.contains("aaa\x1b[1A\r$ echo aaa"), "got: {stdout:?}");
+    assert!(
+        stdout.contains("\nb aaaaaaaaaaaaaaaaaaaaaaaa\n"),
+        "got: {stdout}"
+    );
+}
+
+#[test]
+fn typing_past_the_last_column_moves_to_next_row() {
+    let output = run_shell_with_env(&[], &[("COLUMNS", "10")], "echo 12345\nexit\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("$ echo 123\r\n45\r\n"), "got: {stdout:?}");
+}
+
+#[test]
+fn completion_listing_uses_terminal_width() {
+    let dir = tempfile::tempdir().unwrap();
+    for name in ["abc_one", "abc_two", "abc_three", "abc_four"] {
+        let path = dir.path().join(name);
+        std::fs::write(&path, "").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    let output = run_shell_with_env(
+        &[],
+        &[("COLUMNS", "24"), ("PATH", dir.path().to_str().unwrap())],
+        "abc_\t\t\x03exit\n",
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("\r\nabc_four   abc_three\r\nabc_one    abc_two\x1b[2A"),
+        "got: {stdout:?}"
+    );
+}
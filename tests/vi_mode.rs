@@ -0,0 +1,62 @@
+mod common;
+use common::{run_shell, run_shell_with_env};
+
+fn run_vi(keys: &str) -> String {
+    let output = run_shell(&[], &format!("set -o vi\n{keys}\nexit\n"));
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn prompt_shows_mode() {
+    let stdout = run_vi("echo hi\x1bh");
+    assert!(stdout.contains("(ins)$ echo hi"), "got: {stdout:?}");
+    assert!(stdout.contains("(cmd)$ echo hi"), "got: {stdout:?}");
+}
+
+#[test]
+fn operator_with_motion() {
+    let stdout = run_vi("echo one two\x1bbdw");
+    assert!(stdout.contains("\none\n"), "got: {stdout}");
+}
+
+#[test]
+fn change_word_and_repeat() {
+    let stdout = run_vi("echo a b c\x1b0wcwx\x1bw.");
+    assert!(stdout.contains("\nx x c\n"), "got: {stdout}");
+}
+
+#[test]
+fn delete_character_and_undo() {
+    let stdout = run_vi("echo abc\x1bxxuu");
+    assert!(stdout.contains("\nabc\n"), "got: {stdout}");
+}
+
+#[test]
+fn k_recalls_history() {
+    let stdout = run_vi("echo first\necho second\n\x1bkk");
+    assert_eq!(stdout.matches("\nfirst\n").count(), 2, "got: {stdout}");
+}
+
+#[test]
+fn slash_searches_history() {
+    let stdout = run_vi("echo alpha\necho beta\n\x1b/alp\n");
+    assert_eq!(stdout.matches("\nalpha\n").count(), 2, "got: {stdout}");
+}
+
+#[test]
+fn v_edits_line_in_editor() {
+    let output = run_shell_with_env(
+        &[],
+        &[("VISUAL", "sed -i s/one/two/")],
+        "set -o vi\necho one\x1bv\nexit\n",
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("\ntwo\n"), "got: {stdout}");
+}
+
+#[test]
+fn emacs_mode_turns_vi_off() {
+    let stdout = run_vi("set -o emacs\nset -o");
+    assert!(stdout.contains("vi             \toff"), "got: {stdout}");
+    assert!(stdout.contains("emacs          \ton"), "got: {stdout}");
+}